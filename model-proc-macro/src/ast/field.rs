@@ -41,6 +41,34 @@ impl Field {
       Field::Relation(x) => x.emit_foreign_field_function(),
     }
   }
+
+  /// Returns the name of the field if it is a link to a foreign node that can be
+  /// fetched.
+  pub fn fetchable_name(&self) -> Option<&str> {
+    match self {
+      Field::ForeignNode(x) => Some(&x.name),
+      _ => None,
+    }
+  }
+
+  /// Returns the name and the SurrealQL type of the field if it declares one.
+  pub fn typed_name(&self) -> Option<(&str, &str)> {
+    match self {
+      Field::Property(x) => x.sql_type.as_deref().map(|t| (x.name.as_str(), t)),
+      Field::ForeignNode(x) => x.sql_type.as_deref().map(|t| (x.name.as_str(), t)),
+      Field::Relation(_) => None,
+    }
+  }
+
+  /// Returns the name of the field if it is stored on the node itself, as opposed
+  /// to the relations that are stored on edges.
+  pub fn stored_name(&self) -> Option<&str> {
+    match self {
+      Field::Property(x) => Some(&x.name),
+      Field::ForeignNode(x) => Some(&x.name),
+      Field::Relation(_) => None,
+    }
+  }
 }
 
 /// A simple property
@@ -49,6 +77,10 @@ pub struct FieldProperty {
   pub name: String,
 
   pub is_public: bool,
+
+  /// The SurrealQL type declared after the name, like `string` in
+  /// `handle: string`.
+  pub sql_type: Option<String>,
 }
 
 impl FieldProperty {
@@ -92,6 +124,10 @@ pub struct FieldForeignNode {
   pub foreign_type: String,
 
   pub is_public: bool,
+
+  /// The SurrealQL type declared after the foreign type, like `record<Account>`
+  /// in `author<Account>: record<Account>`.
+  pub sql_type: Option<String>,
 }
 
 impl FieldForeignNode {
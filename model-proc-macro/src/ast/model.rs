@@ -11,6 +11,56 @@ use super::Field;
 pub struct Model {
   pub name: String,
   pub fields: Vec<Field>,
+  pub indexes: Vec<Index>,
+}
+
+impl Model {
+  pub fn new(name: String, entries: Vec<Entry>) -> Self {
+    let mut fields = Vec::new();
+    let mut indexes = Vec::new();
+
+    for entry in entries {
+      match entry {
+        Entry::Field(field) => fields.push(field),
+        Entry::Index(index) => indexes.push(index),
+      };
+    }
+
+    Self {
+      name,
+      fields,
+      indexes,
+    }
+  }
+}
+
+/// An entry of the model, either a field or an index over its fields.
+#[derive(Debug)]
+pub enum Entry {
+  Field(Field),
+  Index(Index),
+}
+
+/// An index of the table, like `@unique account_handle(handle)`.
+#[derive(Debug, Clone)]
+pub struct Index {
+  pub name: String,
+  pub fields: Vec<String>,
+  pub is_unique: bool,
+}
+
+impl Index {
+  fn emit_schema_index(&self) -> TokenStream {
+    let name = &self.name;
+    let fields = &self.fields;
+    let is_unique = self.is_unique;
+
+    quote!(SchemaIndex {
+      name: #name,
+      fields: &[#(#fields),*],
+      is_unique: #is_unique,
+    })
+  }
 }
 
 impl Display for Model {
@@ -47,6 +97,30 @@ impl Display for Model {
       .map(|field| field.emit_foreign_field_function())
       .collect();
 
+    let fetchable_names: Vec<&str> = self
+      .fields
+      .iter()
+      .filter_map(|field| field.fetchable_name())
+      .collect();
+
+    let stored_names: Vec<&str> = self
+      .fields
+      .iter()
+      .filter_map(|field| field.stored_name())
+      .collect();
+
+    let (typed_names, sql_types): (Vec<&str>, Vec<&str>) = self
+      .fields
+      .iter()
+      .filter_map(|field| field.typed_name())
+      .unzip();
+
+    let schema_indexes: Vec<TokenStream> = self
+      .indexes
+      .iter()
+      .map(|index| index.emit_schema_index())
+      .collect();
+
     let implementations = quote! {
       impl<const N: usize> #name<N> {
         const label: &'static str = stringify!(#name);
@@ -67,21 +141,72 @@ impl Display for Model {
         }
 
         #(#field_foreign_functions)*
+
+        /// Returns the names of the fields that link to foreign nodes, to pass
+        /// them to a FETCH clause.
+        pub fn fetch_relations(&self) -> &'static [&'static str] {
+          &[#(#fetchable_names),*]
+        }
+
+        /// Returns the names of the fields stored on the node itself, the
+        /// relations excluded.
+        pub fn field_names(&self) -> &'static [&'static str] {
+          &[#(#stored_names),*]
+        }
+
+        /// Returns the names of the fields that declare a SurrealQL type, along
+        /// with their type.
+        pub fn field_types(&self) -> &'static [(&'static str, &'static str)] {
+          &[#((#typed_names, #sql_types)),*]
+        }
+
+        /// Returns the indexes declared on the table.
+        pub fn indexes(&self) -> &'static [SchemaIndex] {
+          &[#(#schema_indexes),*]
+        }
+
+        /// Returns the name of the table as it is declared, unlike the Display
+        /// output of the model which escapes it when it is a reserved word.
+        pub fn table_name(&self) -> &'static str {
+          Self::label
+        }
       }
 
       impl<const N: usize> std::fmt::Display for #name<N> {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-          write!(f, "{}", Self::label)
+          write!(f, "{}", escape_field(Self::label))
         }
       }
 
-      impl<const N: usize> Into<std::borrow::Cow<'static, str>> for #name<N> {
-        fn into(self) -> std::borrow::Cow<'static, str> {
-          std::borrow::Cow::from(Self::label)
+      impl<'a, const N: usize> Into<std::borrow::Cow<'a, str>> for #name<N> {
+        fn into(self) -> std::borrow::Cow<'a, str> {
+          escape_field(Self::label)
         }
       }
 
       impl<const N: usize> ToNodeBuilder for #name<N> {}
+
+      impl<const N: usize> Table for #name<N> {
+        const TABLE: &'static str = Self::label;
+      }
+
+      impl<const N: usize> SchemaModel for #name<N> {
+        fn field_names(&self) -> &'static [&'static str] {
+          #name::field_names(self)
+        }
+
+        fn table_name(&self) -> &'static str {
+          #name::table_name(self)
+        }
+
+        fn field_types(&self) -> &'static [(&'static str, &'static str)] {
+          #name::field_types(self)
+        }
+
+        fn indexes(&self) -> &'static [SchemaIndex] {
+          #name::indexes(self)
+        }
+      }
     };
 
     let output = quote! {
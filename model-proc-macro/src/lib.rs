@@ -79,6 +79,42 @@ lalrpop_mod!(parser);
 /// }
 /// ```
 /// 
+/// ## Fetching foreign nodes
+///
+/// The fields declared as foreign nodes (`friends<Vec<Account>>`) are listed by
+/// the generated `fetch_relations()` method, which can be passed directly to
+/// `QueryBuilder::fetch_many` to fetch every link of the model:
+///
+/// ```rs
+/// let query = QueryBuilder::new()
+///   .select("*")
+///   .from(account)
+///   .fetch_many(account.fetch_relations())
+///   .build();
+///
+/// assert_eq!(query, "SELECT * FROM Account FETCH friends");
+/// ```
+///
+/// Similarly the generated `field_names()` method lists every field stored on the
+/// node itself, public or not, leaving out the relations.
+///
+/// ## Field types and indexes
+///
+/// A field can declare its SurrealQL type after a `:`, and the indexes of the
+/// table are declared with `@index name(fields)` or `@unique name(fields)`. They
+/// are listed by the generated `field_types()` and `indexes()` methods and are
+/// used by `SchemaModel::define_statements()` and the schema drift detection:
+///
+/// ```rs
+/// model!(Account {
+///   id,
+///   pub handle: string,
+///   pub friend<Account>: option<record<Account>>,
+///
+///   @unique account_handle(handle),
+/// });
+/// ```
+///
 /// ## public & private fields
 /// 
 /// The QueryBuilder type offers a series of methods to quickly list the fields of your
@@ -0,0 +1,129 @@
+use std::fmt::Display;
+
+use crate::querybuilder::QueryBuilder;
+
+/// Builds an aggregation query from the fields to group the rows by and the
+/// named aggregate expressions to compute for every group, and lists the fields
+/// of the resulting rows so the deserialization target can be checked against
+/// the query.
+///
+/// The rows are aggregated all together with `GROUP ALL` when there are no
+/// fields to group them by.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let aggregate = Aggregate::new("Order")
+///   .group_by("customer.country")
+///   .aggregate("orders", "count()")
+///   .aggregate("revenue", "math::sum(price)")
+///   .filter("status = 'paid'");
+///
+/// assert_eq!(vec!["customer", "orders", "revenue"], aggregate.result_fields());
+/// assert_eq!(
+///   "SELECT customer.country, count() AS orders, math::sum(price) AS revenue \
+///    FROM Order WHERE (status = 'paid') GROUP BY customer.country",
+///   aggregate.build().build()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aggregate {
+  target: String,
+  groups: Vec<String>,
+  aggregates: Vec<(String, String)>,
+  conditions: Vec<String>,
+}
+
+impl Aggregate {
+  /// Start an aggregation of the rows of the `target`.
+  pub fn new<T: Display>(target: T) -> Self {
+    Self {
+      target: target.to_string(),
+      groups: Vec::new(),
+      aggregates: Vec::new(),
+      conditions: Vec::new(),
+    }
+  }
+
+  /// Group the rows by the `field`, which is also projected.
+  pub fn group_by<F: Display>(mut self, field: F) -> Self {
+    self.groups.push(field.to_string());
+
+    self
+  }
+
+  /// Compute the aggregate `expression` for every group, projected as `alias`.
+  pub fn aggregate<E: Display>(mut self, alias: &str, expression: E) -> Self {
+    self
+      .aggregates
+      .push((alias.to_owned(), expression.to_string()));
+
+    self
+  }
+
+  /// Only aggregate the rows satisfying the `condition`.
+  pub fn filter<T: Display>(mut self, condition: T) -> Self {
+    self.conditions.push(condition.to_string());
+
+    self
+  }
+
+  /// Return the names of the top-level fields of the resulting rows, in the
+  /// order of the projection. The nested group fields like `customer.country`
+  /// are returned as the object holding them.
+  pub fn result_fields(&self) -> Vec<&str> {
+    let groups = self
+      .groups
+      .iter()
+      .map(|field| field.split('.').next().unwrap_or(field));
+
+    let aliases = self.aggregates.iter().map(|(alias, _)| alias.as_str());
+
+    let mut fields: Vec<&str> = Vec::new();
+    for field in groups.chain(aliases) {
+      if !fields.contains(&field) {
+        fields.push(field);
+      }
+    }
+
+    fields
+  }
+
+  /// Build the aggregation into a query builder, to bind its parameters or add
+  /// more clauses to it.
+  pub fn build<'a>(self) -> QueryBuilder<'a> {
+    let projections: Vec<String> = self
+      .groups
+      .iter()
+      .cloned()
+      .chain(
+        self
+          .aggregates
+          .iter()
+          .map(|(alias, expression)| format!("{expression} AS {alias}")),
+      )
+      .collect();
+
+    let mut query = QueryBuilder::new()
+      .select(projections.join(", "))
+      .from(self.target);
+
+    if !self.conditions.is_empty() {
+      // every condition is parenthesized so the `OR` they hold stay within them
+      let conditions: Vec<String> = self
+        .conditions
+        .iter()
+        .map(|condition| format!("({condition})"))
+        .collect();
+
+      query = query.filter(conditions.join(" AND "));
+    }
+
+    if self.groups.is_empty() {
+      return query.segment("GROUP ALL");
+    }
+
+    query.group_by(self.groups.join(", "))
+  }
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::model::SqlSerializeError;
+use crate::model::SqlSerializeResult;
+
+/// The values bound to the `$parameters` of a query, sent alongside the query
+/// text to the database rather than written into it.
+///
+/// The keys are stored without their leading `$`, and the values are serialized
+/// as soon as they are bound.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let mut bindings = Bindings::new();
+/// bindings.insert("$handle", "John").unwrap();
+/// bindings.insert("age", 42).unwrap();
+///
+/// assert_eq!(2, bindings.len());
+/// assert_eq!(Some(&serde_json::json!("John")), bindings.get("handle"));
+/// assert_eq!(Some(&serde_json::json!(42)), bindings.get("$age"));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Bindings {
+  values: BTreeMap<String, Value>,
+}
+
+impl Bindings {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Serialize the `value` and bind it to the `key` parameter, replacing the
+  /// previous value if any. The leading `$` of the key is optional.
+  pub fn insert<T: Serialize>(&mut self, key: &str, value: T) -> SqlSerializeResult<()> {
+    self.insert_value(key, to_value(value)?);
+
+    Ok(())
+  }
+
+  /// Bind the already serialized `value` to the `key` parameter, which cannot
+  /// fail unlike [`Bindings::insert()`]. Return the value it replaces, if any.
+  pub(crate) fn insert_value(&mut self, key: &str, value: Value) -> Option<Value> {
+    self
+      .values
+      .insert(key.trim_start_matches('$').to_owned(), value)
+  }
+
+  /// Unbind the `key` parameter.
+  pub(crate) fn remove(&mut self, key: &str) {
+    self.values.remove(key.trim_start_matches('$'));
+  }
+
+  /// Move every value of `other` into the bindings, the values of `other` win
+  /// over the ones bound to the same parameters.
+  pub fn merge(&mut self, other: Bindings) {
+    self.values.extend(other.values);
+  }
+
+  /// Return the value bound to the `key` parameter. The leading `$` of the key is
+  /// optional.
+  pub fn get(&self, key: &str) -> Option<&Value> {
+    self.values.get(key.trim_start_matches('$'))
+  }
+
+  /// Iterate over the parameters, without their leading `$`, and their values.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+    self.values.iter().map(|(key, value)| (key.as_str(), value))
+  }
+
+  /// Return the bindings as a JSON object, like the variables expected by the
+  /// HTTP `/sql` endpoint of SurrealDB.
+  pub fn to_json(&self) -> Map<String, Value> {
+    self
+      .iter()
+      .map(|(key, value)| (key.to_owned(), value.clone()))
+      .collect()
+  }
+
+  /// Return the bindings as a JSON object string, ready to be handed to
+  /// JavaScript when the crate is compiled to WebAssembly.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let mut bindings = Bindings::new();
+  /// bindings.insert("handle", "John").unwrap();
+  ///
+  /// assert_eq!(r#"{"handle":"John"}"#, bindings.to_json_string());
+  /// ```
+  pub fn to_json_string(&self) -> String {
+    Value::Object(self.to_json()).to_string()
+  }
+
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+}
+
+/// Serialize the `value` the way [`Bindings::insert()`] does.
+pub(crate) fn to_value<T: Serialize>(value: T) -> SqlSerializeResult<Value> {
+  serde_json::to_value(value).map_err(|e| SqlSerializeError::Message(e.to_string()))
+}
+
+impl From<Bindings> for Map<String, Value> {
+  fn from(bindings: Bindings) -> Self {
+    bindings.values.into_iter().collect()
+  }
+}
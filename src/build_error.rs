@@ -0,0 +1,132 @@
+use std::fmt::{self, Display};
+
+use crate::dialect::Dialect;
+
+pub type QueryBuilderResult<T> = std::result::Result<T, QueryBuilderError>;
+
+/// The reasons why [`QueryBuilder::try_build()`](crate::querybuilder::QueryBuilder::try_build)
+/// refused to build a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryBuilderError {
+  /// A statement has a clause it does not accept, like a LIMIT on a CREATE.
+  UnexpectedClause {
+    statement: &'static str,
+    clause: &'static str,
+  },
+
+  /// A clause is written after a clause it must precede, like a WHERE before
+  /// the FROM of a SELECT.
+  MisplacedClause {
+    statement: &'static str,
+    clause: &'static str,
+    following: &'static str,
+  },
+
+  /// A statement has the same clause twice, like two WHERE clauses.
+  DuplicateClause {
+    statement: &'static str,
+    clause: &'static str,
+  },
+
+  /// The query uses parameters that are not bound, their names are listed with
+  /// their `$`.
+  UnboundParameters(Vec<String>),
+
+  /// Unchecked text was inserted in the query, the methods that inserted it
+  /// are listed.
+  RawText(Vec<&'static str>),
+
+  /// A graph traversal has an arrow leading nowhere, like a trailing `->` or
+  /// two arrows in a row. The traversal is included.
+  MalformedTraversal(String),
+
+  /// The query uses a syntax the targeted version of SurrealDB does not
+  /// support, like an UPSERT in 1.x.
+  UnsupportedSyntax {
+    syntax: &'static str,
+    dialect: Dialect,
+  },
+
+  /// The rendered query is longer than the allowed number of bytes.
+  OutputTooLong { length: usize, limit: usize },
+
+  /// The query is made of more segments than allowed.
+  TooManySegments { count: usize, limit: usize },
+
+  /// A page was requested with a zero number or size, while pages are
+  /// numbered from 1 and hold at least one row.
+  InvalidPage { page: u64, per_page: u64 },
+
+  /// The parser of SurrealDB rejected the query. The position is the line and
+  /// column of the error, when the parser reports it.
+  InvalidSyntax {
+    message: String,
+    position: Option<(usize, usize)>,
+  },
+}
+
+impl Display for QueryBuilderError {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      QueryBuilderError::UnexpectedClause { statement, clause } => {
+        write!(
+          formatter,
+          "a {statement} statement cannot have a {clause} clause"
+        )
+      }
+      QueryBuilderError::MisplacedClause {
+        statement,
+        clause,
+        following,
+      } => write!(
+        formatter,
+        "the {clause} clause of a {statement} statement must come before its {following} clause"
+      ),
+      QueryBuilderError::DuplicateClause { statement, clause } => write!(
+        formatter,
+        "a {statement} statement cannot have more than one {clause} clause"
+      ),
+      QueryBuilderError::UnboundParameters(parameters) => write!(
+        formatter,
+        "the parameters {} are not bound",
+        parameters.join(", ")
+      ),
+      QueryBuilderError::RawText(methods) => write!(
+        formatter,
+        "the query holds raw text inserted by {}",
+        methods
+          .iter()
+          .map(|method| format!("{method}()"))
+          .collect::<Vec<_>>()
+          .join(", ")
+      ),
+      QueryBuilderError::MalformedTraversal(traversal) => {
+        write!(
+          formatter,
+          "the traversal {traversal} has an arrow leading nowhere"
+        )
+      }
+      QueryBuilderError::UnsupportedSyntax { syntax, dialect } => write!(
+        formatter,
+        "the {syntax} syntax is not supported by SurrealDB {dialect}"
+      ),
+      QueryBuilderError::OutputTooLong { length, limit } => write!(
+        formatter,
+        "the query is {length} bytes long, more than the limit of {limit}"
+      ),
+      QueryBuilderError::TooManySegments { count, limit } => write!(
+        formatter,
+        "the query is made of {count} segments, more than the limit of {limit}"
+      ),
+      QueryBuilderError::InvalidPage { page, per_page } => write!(
+        formatter,
+        "there is no page {page} of {per_page} rows, pages are numbered from 1 and hold at least one row"
+      ),
+      QueryBuilderError::InvalidSyntax { message, .. } => {
+        write!(formatter, "the query is not valid SurrealQL: {message}")
+      }
+    }
+  }
+}
+
+impl std::error::Error for QueryBuilderError {}
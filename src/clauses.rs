@@ -0,0 +1,388 @@
+/// The keywords starting the clauses of a query, the ones made of several words
+/// come first so they are matched before their first word. They are matched in
+/// uppercase only, the way the builder writes them, so the lowercase fields
+/// named like a keyword such as `content` or `start` are not mistaken for one.
+const CLAUSE_KEYWORDS: &[&str] = &[
+  "GROUP BY", "ORDER BY", "SPLIT ON", "SELECT", "FROM", "WHERE", "SPLIT", "GROUP", "ORDER",
+  "LIMIT", "START", "FETCH", "TIMEOUT", "PARALLEL", "CREATE", "UPDATE", "RELATE", "DELETE",
+  "INSERT", "UPSERT", "INTO", "SET", "CONTENT", "MERGE", "RETURN",
+];
+
+/// The keywords of the clauses written after the WHERE clause of a statement.
+const AFTER_WHERE_KEYWORDS: &[&str] = &[
+  "SPLIT ON", "SPLIT", "GROUP BY", "GROUP", "ORDER BY", "ORDER", "LIMIT", "START", "FETCH",
+  "TIMEOUT", "PARALLEL", "RETURN",
+];
+
+/// The keywords of the clauses written after the ORDER BY clause of a statement.
+const AFTER_ORDER_KEYWORDS: &[&str] = &["LIMIT", "START", "FETCH", "TIMEOUT", "PARALLEL"];
+
+/// Keeps track of the string literals, escaped identifiers and brackets while
+/// walking through a query, so the keywords inside them can be told apart from
+/// the top-level ones.
+#[derive(Default)]
+struct Scanner {
+  depth: usize,
+
+  /// The character closing the current string literal or escaped identifier.
+  quote: Option<char>,
+  escaped: bool,
+}
+
+impl Scanner {
+  /// Advance over `c` and return whether it is outside of any string literal
+  /// or escaped identifier, the depth is updated after the character is read.
+  fn advance(&mut self, c: char) -> bool {
+    if let Some(closing) = self.quote {
+      match c {
+        _ if self.escaped => self.escaped = false,
+        '\\' => self.escaped = true,
+        _ if c == closing => self.quote = None,
+        _ => {}
+      };
+
+      return false;
+    }
+
+    match c {
+      '\'' | '"' | '`' => self.quote = Some(c),
+      '⟨' => self.quote = Some('⟩'),
+      '(' | '[' | '{' => self.depth += 1,
+      ')' | ']' | '}' => self.depth = self.depth.saturating_sub(1),
+      _ => {}
+    };
+
+    true
+  }
+}
+
+/// Return the start and end of every clause keyword found at the top level of
+/// the query, outside of the brackets and string literals.
+pub(crate) fn top_level_keywords(query: &str) -> Vec<(usize, usize)> {
+  let mut keywords = Vec::new();
+  let mut scanner = Scanner::default();
+  let mut previous = ' ';
+
+  for (index, c) in query.char_indices() {
+    let is_top_level = scanner.depth == 0 && scanner.quote.is_none();
+
+    if is_top_level && previous.is_whitespace() {
+      if let Some(keyword) = keyword_at(&query[index..]) {
+        keywords.push((index, index + keyword.len()));
+      }
+    }
+
+    scanner.advance(c);
+    previous = c;
+  }
+
+  keywords
+}
+
+/// Split the query into its trimmed statements, on the semicolons found outside
+/// of the brackets and string literals. The empty statements are skipped.
+pub(crate) fn statements(query: &str) -> Vec<&str> {
+  let mut statements = Vec::new();
+  let mut scanner = Scanner::default();
+  let mut start = 0;
+
+  for (index, c) in query.char_indices() {
+    let is_code = scanner.advance(c);
+
+    if is_code && scanner.depth == 0 && c == ';' {
+      statements.push(query[start..index].trim());
+      start = index + 1;
+    }
+  }
+
+  statements.push(query[start..].trim());
+  statements.retain(|statement| !statement.is_empty());
+
+  statements
+}
+
+/// Split the text on the whitespace found outside of the string literals and
+/// escaped identifiers, so a segment holding a whole clause gives its words one
+/// by one while the literals stay intact.
+pub(crate) fn words(text: &str) -> Vec<&str> {
+  let mut words = Vec::new();
+  let mut scanner = Scanner::default();
+  let mut start = None;
+
+  for (index, c) in text.char_indices() {
+    let is_code = scanner.quote.is_none();
+    scanner.advance(c);
+
+    match start {
+      Some(word) if is_code && c.is_whitespace() => {
+        words.push(&text[word..index]);
+        start = None;
+      }
+      None if !is_code || !c.is_whitespace() => start = Some(index),
+      _ => {}
+    };
+  }
+
+  if let Some(word) = start {
+    words.push(&text[word..]);
+  }
+
+  words
+}
+
+/// Remove the comments of the query, the `--`, `#` and `//` comments running to
+/// the end of their line and the `/* */` ones. The comment markers in the string
+/// literals are kept.
+pub(crate) fn strip_comments(query: &str) -> String {
+  let mut output = String::with_capacity(query.len());
+  let mut scanner = Scanner::default();
+  let mut rest = query;
+
+  while let Some(c) = rest.chars().next() {
+    let is_code = scanner.quote.is_none();
+    let end = if !is_code {
+      None
+    } else if rest.starts_with("--") || rest.starts_with('#') || rest.starts_with("//") {
+      Some(rest.find('\n').unwrap_or(rest.len()))
+    } else if rest.starts_with("/*") {
+      Some(rest.find("*/").map_or(rest.len(), |end| end + 2))
+    } else {
+      None
+    };
+
+    match end {
+      Some(end) => {
+        output.push(' ');
+        rest = &rest[end..];
+      }
+      None => {
+        scanner.advance(c);
+        output.push(c);
+        rest = &rest[c.len_utf8()..];
+      }
+    };
+  }
+
+  output
+}
+
+/// Return the names of the `$parameters` used by the query, without their `$`,
+/// in the order they appear. The ones inside string literals are skipped.
+pub(crate) fn parameters(query: &str) -> Vec<&str> {
+  let mut parameters = Vec::new();
+  let mut scanner = Scanner::default();
+
+  for (index, c) in query.char_indices() {
+    if scanner.advance(c) && c == '$' {
+      let name = &query[index + 1..];
+      let length = name
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(name.len());
+
+      if length > 0 {
+        parameters.push(&name[..length]);
+      }
+    }
+  }
+
+  parameters
+}
+
+/// Collapse the whitespace of the query outside of the string literals into
+/// single spaces, and remove it entirely after the opening brackets and before
+/// the closing ones, as well as around the commas and semicolons.
+pub(crate) fn compact(query: &str) -> String {
+  let mut output = String::with_capacity(query.len());
+  let mut scanner = Scanner::default();
+  let mut pending_space = false;
+
+  for c in query.trim().chars() {
+    let is_code = scanner.quote.is_none();
+    scanner.advance(c);
+
+    if is_code && c.is_whitespace() {
+      pending_space = true;
+      continue;
+    }
+
+    let previous = output.chars().next_back();
+    let tight = is_code && matches!(c, ',' | ';' | ')' | ']' | '}')
+      || matches!(previous, Some(',' | ';' | '(' | '[' | '{'));
+
+    if pending_space && !tight {
+      output.push(' ');
+    }
+
+    pending_space = false;
+    output.push(c);
+  }
+
+  output
+}
+
+/// Return the start and end of every graph arrow of the query, the `->`, `<-`
+/// and `<->` found outside of the string literals.
+pub(crate) fn arrows(query: &str) -> Vec<(usize, usize)> {
+  let mut arrows = Vec::new();
+  let mut scanner = Scanner::default();
+  let mut end = 0;
+
+  for (index, c) in query.char_indices() {
+    if !scanner.advance(c) || index < end {
+      continue;
+    }
+
+    let rest = &query[index..];
+    let arrow = ["<->", "->", "<-"]
+      .iter()
+      .find(|arrow| rest.starts_with(**arrow));
+
+    if let Some(arrow) = arrow {
+      end = index + arrow.len();
+      arrows.push((index, end));
+    }
+  }
+
+  arrows
+}
+
+/// Return whether the `pattern` is found in the query outside of the string
+/// literals.
+pub(crate) fn contains_code(query: &str, pattern: &str) -> bool {
+  let mut scanner = Scanner::default();
+
+  query
+    .char_indices()
+    .any(|(index, c)| scanner.advance(c) && query[index..].starts_with(pattern))
+}
+
+/// Return the index of the bracket closing the one at `opening`.
+pub(crate) fn closing_bracket(query: &str, opening: usize) -> Option<usize> {
+  let mut scanner = Scanner::default();
+
+  for (index, c) in query[opening..].char_indices() {
+    scanner.advance(c);
+
+    if scanner.depth == 0 && scanner.quote.is_none() {
+      return Some(opening + index);
+    }
+  }
+
+  None
+}
+
+/// Return the clause keyword the text starts with, if any. See
+/// `CLAUSE_KEYWORDS` for why only the uppercase keywords are matched.
+pub(crate) fn keyword_at(text: &str) -> Option<&'static str> {
+  CLAUSE_KEYWORDS.iter().copied().find(|keyword| {
+    text.starts_with(keyword)
+      && text[keyword.len()..]
+        .chars()
+        .next()
+        .is_none_or(|c| c.is_whitespace())
+  })
+}
+
+/// Return whether the segment starts a clause written after the WHERE clause.
+pub(crate) fn follows_where(segment: &str) -> bool {
+  keyword_at(segment).is_some_and(|keyword| AFTER_WHERE_KEYWORDS.contains(&keyword))
+}
+
+/// Return whether the segment starts a clause written after the ORDER BY clause.
+pub(crate) fn follows_order(segment: &str) -> bool {
+  keyword_at(segment).is_some_and(|keyword| AFTER_ORDER_KEYWORDS.contains(&keyword))
+}
+
+/// Return whether the condition is made of groups in parenthesis joined with
+/// AND, like `(a OR b) AND (c)`, so another condition can be joined to it with
+/// an AND without grouping it first.
+pub(crate) fn is_conjunction(condition: &str) -> bool {
+  let condition = condition.trim();
+  let mut scanner = Scanner::default();
+  let mut outside = String::new();
+  let mut groups = 0;
+
+  for c in condition.chars() {
+    let depth = scanner.depth;
+    let is_code = scanner.advance(c);
+
+    match (depth, scanner.depth) {
+      // a string literal or escaped identifier outside of the groups
+      (0, 0) if !is_code || scanner.quote.is_some() => return false,
+      (0, 0) => outside.push(c),
+      (0, _) => groups += 1,
+      _ => {}
+    };
+  }
+
+  let words: Vec<&str> = outside.split_whitespace().collect();
+
+  condition.starts_with('(')
+    && condition.ends_with(')')
+    && words.iter().all(|word| *word == "AND")
+    && words.len() + 1 == groups
+}
+
+/// Render the query with every clause on its own line, and the sub-queries
+/// indented on the lines between their parentheses.
+pub(crate) fn pretty(query: &str, depth: usize) -> String {
+  let indent = "  ".repeat(depth);
+  let keywords = top_level_keywords(query);
+  let mut lines = Vec::new();
+
+  let prefix = query[..keywords.first().map_or(query.len(), |(start, _)| *start)].trim();
+  if !prefix.is_empty() {
+    lines.push(format!("{indent}{}", expand_subqueries(prefix, depth)));
+  }
+
+  for (i, (start, end)) in keywords.iter().enumerate() {
+    let next = keywords.get(i + 1).map_or(query.len(), |(start, _)| *start);
+    let content = query[*end..next].trim();
+
+    match content.is_empty() {
+      true => lines.push(format!("{indent}{}", &query[*start..*end])),
+      false => lines.push(format!(
+        "{indent}{} {}",
+        &query[*start..*end],
+        expand_subqueries(content, depth)
+      )),
+    };
+  }
+
+  lines.join("\n")
+}
+
+/// Replace the sub-queries of the text, the parentheses starting with a clause
+/// keyword, with their indented pretty version.
+fn expand_subqueries(text: &str, depth: usize) -> String {
+  let indent = "  ".repeat(depth);
+  let mut output = String::with_capacity(text.len());
+  let mut scanner = Scanner::default();
+  let mut index = 0;
+
+  while let Some(c) = text[index..].chars().next() {
+    let is_code = scanner.quote.is_none();
+    let inner = &text[index + 1..];
+
+    if is_code && c == '(' && keyword_at(inner.trim_start()).is_some() {
+      if let Some(closing) = closing_bracket(text, index) {
+        let subquery = text[index + 1..closing].trim();
+
+        output += "(\n";
+        output += &pretty(subquery, depth + 1);
+        output += "\n";
+        output += &indent;
+        output += ")";
+
+        index = closing + 1;
+        continue;
+      }
+    }
+
+    scanner.advance(c);
+    output.push(c);
+    index += c.len_utf8();
+  }
+
+  output
+}
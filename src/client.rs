@@ -0,0 +1,83 @@
+//! The `surrealdb::sql::Thing` record ids of the client convert from and into
+//! the [RecordId](crate::foreign_key::RecordId) and
+//! [TypedKey](crate::foreign_key::TypedKey) of the crate, and can be the key type
+//! of a `Foreign<V, Thing>` link:
+//!
+//! ```rs
+//! let author = RecordId::from(thing.clone());
+//!
+//! // the record id is written in the query, or bound as its `table:id` text
+//! let query = QueryBuilder::new().select("*").from(&author).build();
+//! let query = QueryBuilder::new()
+//!   .select("*")
+//!   .from("File")
+//!   .filter("author = type::thing($author)")
+//!   .bind("author", &author)?;
+//!
+//! let author: TypedKey<Account> = thing.try_into()?;
+//! let link: Foreign<Account, Thing> = Foreign::new_key(Thing::from(author));
+//! ```
+//!
+//! The client receives the bindings as JSON values, where a `Thing` would be an
+//! object rather than a record, so it is bound through its [RecordId].
+
+use serde::de::DeserializeOwned;
+use surrealdb::Connection;
+use surrealdb::Response;
+use surrealdb::Surreal;
+
+use crate::querybuilder::Query;
+use crate::querybuilder::QueryBuilder;
+use crate::querybuilder::TypedQuery;
+
+impl<'a> QueryBuilder<'a> {
+  /// Build the query and run it with its bindings on the database, returning the
+  /// response of the client so the result of every statement can be taken from
+  /// it. The rows of the last statement are returned directly by
+  /// [`QueryBuilder::fetch_all()`] and [`QueryBuilder::fetch_one()`], as the
+  /// client is a [QueryRunner](crate::runner::QueryRunner).
+  ///
+  /// ```rs
+  /// let mut response = QueryBuilder::new()
+  ///   .create(account)
+  ///   .set_model(&account)?
+  ///   .raw(";")
+  ///   .select("*")
+  ///   .from(account)
+  ///   .execute(&db)
+  ///   .await?;
+  ///
+  /// let accounts: Vec<Account> = response.take(1)?;
+  ///
+  /// // or directly the rows of the last statement
+  /// let accounts = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from(account)
+  ///   .fetch_all::<Account, _>(&db)
+  ///   .await?;
+  /// ```
+  pub async fn execute<C: Connection>(self, db: &Surreal<C>) -> surrealdb::Result<Response> {
+    let (query, bindings) = self.build_with_bindings();
+    let future = db.query(&query).bind(&bindings);
+
+    crate::trace::executing(&query, &bindings, future).await
+  }
+}
+
+impl Query {
+  /// Run the query with its bindings on the database, see
+  /// [`QueryBuilder::execute()`].
+  pub async fn execute<C: Connection>(&self, db: &Surreal<C>) -> surrealdb::Result<Response> {
+    let future = db.query(self.text()).bind(self.bindings());
+
+    crate::trace::executing(self.text(), self.bindings(), future).await
+  }
+}
+
+impl<T: DeserializeOwned> TypedQuery<'_, T> {
+  /// Run the query with its bindings on the database, see
+  /// [`QueryBuilder::execute()`].
+  pub async fn execute<C: Connection>(self, db: &Surreal<C>) -> surrealdb::Result<Response> {
+    self.into_inner().execute(db).await
+  }
+}
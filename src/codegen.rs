@@ -0,0 +1,319 @@
+/// A table found in a SurrealQL schema, with the fields defined on it.
+struct TableSchema {
+  name: String,
+  fields: Vec<FieldSchema>,
+}
+
+struct FieldSchema {
+  name: String,
+
+  /// The table the field links to when it is a `record<table>`, or an array of
+  /// them.
+  foreign_table: Option<String>,
+}
+
+/// Generate the `model!()` invocations matching the `DEFINE TABLE` and
+/// `DEFINE FIELD` statements of a SurrealQL schema, so the code can be written
+/// to a file from a build script or pasted in the crate using the models.
+///
+/// Every table gets its own public module named after it in snake case, the
+/// fields that are records of another table of the schema become foreign nodes
+/// and every field except the `id` is public. The nested fields like
+/// `settings.theme` are skipped as models are flat, and so are the comments of
+/// the schema.
+///
+/// A model links a single table, so the records of several tables like
+/// `record<Account | Team>` are kept as plain fields. The modules named after a
+/// Rust keyword are written as raw identifiers, like `r#type`.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::codegen::models_from_surql;
+///
+/// let schema = "
+///   DEFINE TABLE Account SCHEMAFULL;
+///   DEFINE FIELD handle ON TABLE Account TYPE string;
+///   DEFINE FIELD settings.theme ON Account TYPE string;
+///   DEFINE FIELD best_friend ON Account TYPE option<record<Account>>;
+///
+///   DEFINE TABLE Project SCHEMAFULL;
+///   DEFINE FIELD name ON Project TYPE string;
+///   DEFINE FIELD authors ON Project TYPE array<record<Account>>;
+/// ";
+///
+/// assert_eq!(
+///   "pub mod account {
+///   use surreal_simple_querybuilder::prelude::*;
+///
+///   model!(Account {
+///     id,
+///     pub handle,
+///     pub best_friend<Account>,
+///   });
+/// }
+///
+/// pub mod project {
+///   use super::account::schema::Account;
+///   use surreal_simple_querybuilder::prelude::*;
+///
+///   model!(Project {
+///     id,
+///     pub name,
+///     pub authors<Account>,
+///   });
+/// }
+/// ",
+///   models_from_surql(schema)
+/// );
+/// ```
+pub fn models_from_surql(schema: &str) -> String {
+  let tables = parse_tables(schema);
+  let mut output = String::new();
+
+  for (i, table) in tables.iter().enumerate() {
+    if i > 0 {
+      output += "\n";
+    }
+
+    output += &format!("pub mod {} {{\n", module_name(&table.name));
+
+    let mut foreign_tables: Vec<&str> = table
+      .fields
+      .iter()
+      .filter_map(|field| field.foreign_table.as_deref())
+      .filter(|foreign| *foreign != table.name)
+      .collect();
+    foreign_tables.sort();
+    foreign_tables.dedup();
+
+    for foreign in foreign_tables {
+      output += &format!(
+        "  use super::{}::schema::{foreign};\n",
+        module_name(foreign)
+      );
+    }
+
+    output += "  use surreal_simple_querybuilder::prelude::*;\n\n";
+    output += &format!("  model!({} {{\n", table.name);
+    output += "    id,\n";
+
+    for field in &table.fields {
+      match &field.foreign_table {
+        Some(foreign) => output += &format!("    pub {}<{foreign}>,\n", field.name),
+        None => output += &format!("    pub {},\n", field.name),
+      };
+    }
+
+    output += "  });\n}\n";
+  }
+
+  output
+}
+
+/// Collect the tables and their fields in the order they are defined in.
+fn parse_tables(schema: &str) -> Vec<TableSchema> {
+  let mut tables: Vec<TableSchema> = Vec::new();
+  let mut fields: Vec<(String, String, Option<String>)> = Vec::new();
+  let schema = crate::clauses::strip_comments(schema);
+
+  for statement in crate::clauses::statements(&schema) {
+    let words: Vec<&str> = statement
+      .split_whitespace()
+      .filter(|word| !is_modifier(word))
+      .collect();
+
+    match words.as_slice() {
+      [define, table, name, ..]
+        if define.eq_ignore_ascii_case("DEFINE") && table.eq_ignore_ascii_case("TABLE") =>
+      {
+        table_entry(&mut tables, unescape(name));
+      }
+      [define, field, name, on, rest @ ..]
+        if define.eq_ignore_ascii_case("DEFINE")
+          && field.eq_ignore_ascii_case("FIELD")
+          && on.eq_ignore_ascii_case("ON") =>
+      {
+        let rest = match rest.first() {
+          Some(word) if word.eq_ignore_ascii_case("TABLE") => &rest[1..],
+          _ => rest,
+        };
+
+        let Some(table) = rest.first() else {
+          continue;
+        };
+
+        let rest = &rest[1..];
+        let field_type = rest
+          .iter()
+          .position(|word| word.eq_ignore_ascii_case("TYPE"))
+          .map(|index| field_type(&rest[index + 1..]));
+
+        let table = unescape(table);
+
+        table_entry(&mut tables, table);
+        fields.push((
+          table.to_string(),
+          unescape(name).to_string(),
+          field_type.and_then(|field_type| record_table(&field_type)),
+        ));
+      }
+      _ => {}
+    };
+  }
+
+  for (table, name, foreign_table) in fields {
+    let is_nested = name.contains(['.', '[']) || name == "id";
+    let foreign_table = foreign_table.filter(|foreign| tables.iter().any(|t| t.name == *foreign));
+
+    if !is_nested {
+      table_entry(&mut tables, &table).fields.push(FieldSchema {
+        name,
+        foreign_table,
+      });
+    }
+  }
+
+  tables
+}
+
+/// Return the table with the given name, after adding it if it was not defined
+/// yet.
+fn table_entry<'a>(tables: &'a mut Vec<TableSchema>, name: &str) -> &'a mut TableSchema {
+  let index = match tables.iter().position(|table| table.name == name) {
+    Some(index) => index,
+    None => {
+      tables.push(TableSchema {
+        name: name.to_owned(),
+        fields: Vec::new(),
+      });
+
+      tables.len() - 1
+    }
+  };
+
+  &mut tables[index]
+}
+
+/// Join the words of the type following a TYPE keyword, the type ends with the
+/// first word that closes its brackets and is not followed by a `|`.
+pub(crate) fn field_type(words: &[&str]) -> String {
+  let mut field_type = String::new();
+  let mut depth = 0;
+
+  for (index, word) in words.iter().enumerate() {
+    if !field_type.is_empty() {
+      field_type.push(' ');
+    }
+
+    field_type.push_str(word);
+
+    for c in word.chars() {
+      match c {
+        '<' | '(' => depth += 1,
+        '>' | ')' => depth -= 1,
+        _ => {}
+      };
+    }
+
+    let is_union = word.ends_with('|')
+      || words
+        .get(index + 1)
+        .is_some_and(|next| next.starts_with('|'));
+
+    if depth <= 0 && !is_union {
+      break;
+    }
+  }
+
+  field_type
+}
+
+/// Remove the `⟨⟩` or the backticks around an escaped name, like the fields
+/// named after a reserved word. The models escape them again in the queries.
+pub(crate) fn unescape(name: &str) -> &str {
+  name
+    .strip_prefix('⟨')
+    .and_then(|name| name.strip_suffix('⟩'))
+    .or_else(|| {
+      name
+        .strip_prefix('`')
+        .and_then(|name| name.strip_suffix('`'))
+    })
+    .unwrap_or(name)
+}
+
+/// Return whether the word is one of the modifiers that may follow DEFINE
+/// TABLE or DEFINE FIELD without changing what is defined.
+fn is_modifier(word: &str) -> bool {
+  ["IF", "NOT", "EXISTS", "OVERWRITE"]
+    .iter()
+    .any(|modifier| word.eq_ignore_ascii_case(modifier))
+}
+
+/// Return the table of a `record<table>` or `record(table)` type, including
+/// when it is wrapped in an `option` or an `array`. The records of several
+/// tables have no single table to link.
+fn record_table(field_type: &str) -> Option<String> {
+  let lowercase = field_type.to_ascii_lowercase();
+  let start = lowercase
+    .find("record<")
+    .or_else(|| lowercase.find("record("))?
+    + "record<".len();
+
+  let tables = &field_type[start..];
+  let end = tables.find(['>', ')']).unwrap_or(tables.len());
+  let tables: Vec<&str> = tables[..end]
+    .split('|')
+    .map(|table| unescape(table.trim()))
+    .collect();
+
+  match tables.as_slice() {
+    [table] if !table.is_empty() => Some(table.to_string()),
+    _ => None,
+  }
+}
+
+/// Return the snake case name of the module holding the model of the table. The
+/// acronyms are kept together, `HTTPLog` gives `http_log`, and the names that
+/// are Rust keywords are written as raw identifiers.
+fn module_name(table: &str) -> String {
+  let chars: Vec<char> = table.chars().collect();
+  let mut name = String::with_capacity(table.len() + 2);
+
+  for (i, c) in chars.iter().enumerate() {
+    let previous = i.checked_sub(1).map(|i| chars[i]);
+    let next = chars.get(i + 1);
+
+    let starts_word = c.is_uppercase()
+      && match previous {
+        Some(previous) if previous.is_uppercase() => next.is_some_and(|next| next.is_lowercase()),
+        Some(previous) => previous != '_',
+        None => false,
+      };
+
+    if starts_word {
+      name.push('_');
+    }
+
+    name.extend(c.to_lowercase());
+  }
+
+  match name.as_str() {
+    // the keywords that cannot be raw identifiers
+    "crate" | "self" | "super" => name + "_",
+    _ if is_keyword(&name) => format!("r#{name}"),
+    _ => name,
+  }
+}
+
+/// Return whether the name is a keyword of Rust, strict or reserved.
+fn is_keyword(name: &str) -> bool {
+  [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "union", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+  ]
+  .contains(&name)
+}
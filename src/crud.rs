@@ -0,0 +1,426 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::dialect::Dialect;
+use crate::escape::escape_field;
+use crate::escape::escape_ident;
+use crate::model::SchemaIndex;
+use crate::model::SchemaModel;
+use crate::model::SqlSerializeError;
+use crate::model::SqlSerializeResult;
+use crate::querybuilder::QueryBuilder;
+use crate::querybuilder::TypedQuery;
+
+/// An opt-in trait building the usual CRUD queries of a table, implemented by
+/// the model of the table for the type `T` of its rows.
+///
+/// The ids passed to the methods may either be complete record ids like
+/// `Account:john` or the id part alone, which is then escaped and prefixed with
+/// the table of the model. The queries that return rows are typed so they can
+/// be executed right away with a [QueryRunner](crate::runner::QueryRunner).
+///
+/// # Example
+/// ```
+/// #![allow(incomplete_features)]
+/// #![feature(generic_const_exprs)]
+/// use serde::Deserialize;
+/// use serde::Serialize;
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// model!(Account {
+///   pub handle,
+/// });
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Account {
+///   handle: String,
+/// }
+///
+/// impl SurrealCrud<Account> for schema::Account<0> {}
+///
+/// let account = schema::model;
+///
+/// assert_eq!("SELECT * FROM Account:john", account.find("john").build());
+/// assert_eq!("DELETE Account:john", account.delete("Account:john").build());
+/// assert_eq!(
+///   "SELECT * FROM Account WHERE handle = $handle",
+///   account.find_where(account.handle.equals_parameterized()).build()
+/// );
+///
+/// let (query, bindings) = account
+///   .create(&Account { handle: "john".to_owned() })
+///   .unwrap()
+///   .build_with_bindings();
+///
+/// assert_eq!("CREATE Account CONTENT $content", query);
+/// assert_eq!(Some(&serde_json::json!({ "handle": "john" })), bindings.get("content"));
+/// ```
+pub trait SurrealCrud<T: Serialize + DeserializeOwned>: SchemaModel {
+  /// The field holding the deletion time of the rows, usually `deleted_at`, to
+  /// opt into the soft deletion of the rows. When set, the queries that read
+  /// rows skip the ones where the field is set and [`SurrealCrud::delete()`]
+  /// sets the field instead of removing the row.
+  ///
+  /// # Example
+  /// ```
+  /// #![allow(incomplete_features)]
+  /// #![feature(generic_const_exprs)]
+  /// use serde::Deserialize;
+  /// use serde::Serialize;
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// model!(Account {
+  ///   pub handle,
+  ///   pub deleted_at,
+  /// });
+  ///
+  /// #[derive(Serialize, Deserialize)]
+  /// struct Account {
+  ///   handle: String,
+  /// }
+  ///
+  /// impl SurrealCrud<Account> for schema::Account<0> {
+  ///   const SOFT_DELETE_FIELD: Option<&'static str> = Some("deleted_at");
+  /// }
+  ///
+  /// let account = schema::model;
+  ///
+  /// assert_eq!(
+  ///   "SELECT * FROM Account WHERE deleted_at IS NONE AND (handle = $handle)",
+  ///   account.find_where("handle = $handle").build()
+  /// );
+  /// assert_eq!(
+  ///   "UPDATE Account:john SET deleted_at = time::now()",
+  ///   account.delete("john").build()
+  /// );
+  /// assert_eq!(
+  ///   "SELECT * FROM Account WHERE handle = $handle",
+  ///   account.with_deleted().find_where("handle = $handle").build()
+  /// );
+  /// assert_eq!(
+  ///   "UPDATE Account:john MERGE $patch WHERE deleted_at IS NONE",
+  ///   account.update("john", &()).unwrap().build()
+  /// );
+  /// ```
+  const SOFT_DELETE_FIELD: Option<&'static str> = None;
+
+  /// The version of SurrealDB the queries target, [`SurrealCrud::save()`]
+  /// writes an `UPDATE` rather than an `UPSERT` for 1.x where `UPDATE` creates
+  /// the missing records.
+  const DIALECT: Dialect = Dialect::V2;
+
+  /// Build a `CREATE` query inserting the `value` bound to the `$content`
+  /// parameter.
+  fn create<'a>(&self, value: &T) -> SqlSerializeResult<TypedQuery<'a, T>> {
+    let query = QueryBuilder::new()
+      .create(self.to_string())
+      .content("$content")
+      .bind("content", value)?;
+
+    Ok(query.into_typed())
+  }
+
+  /// Build an `UPSERT` query of the `value` if it has an `id`, or a `CREATE`
+  /// query otherwise, returning the saved row either way. The `id` is removed
+  /// from the content bound to the `$content` parameter as it is already the
+  /// target of the query.
+  ///
+  /// # Example
+  /// ```
+  /// #![allow(incomplete_features)]
+  /// #![feature(generic_const_exprs)]
+  /// use serde::Deserialize;
+  /// use serde::Serialize;
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// model!(Account {
+  ///   pub handle,
+  /// });
+  ///
+  /// #[derive(Serialize, Deserialize)]
+  /// struct Account {
+  ///   id: Option<String>,
+  ///   handle: String,
+  /// }
+  ///
+  /// impl SurrealCrud<Account> for schema::Account<0> {}
+  ///
+  /// let mut john = Account { id: None, handle: "john".to_owned() };
+  /// let (query, _) = schema::model.save(&john).unwrap().build_with_bindings();
+  /// assert_eq!("CREATE Account CONTENT $content RETURN AFTER", query);
+  ///
+  /// john.id = Some("Account:john".to_owned());
+  /// let (query, bindings) = schema::model.save(&john).unwrap().build_with_bindings();
+  /// assert_eq!("UPSERT Account:john CONTENT $content RETURN AFTER", query);
+  /// assert_eq!(Some(&serde_json::json!({ "handle": "john" })), bindings.get("content"));
+  /// ```
+  fn save<'a>(&self, value: &T) -> SqlSerializeResult<TypedQuery<'a, T>> {
+    let mut content =
+      serde_json::to_value(value).map_err(|e| SqlSerializeError::Message(e.to_string()))?;
+
+    let id = match &mut content {
+      Value::Object(fields) => match fields.remove("id") {
+        Some(Value::String(id)) => Some(id),
+        _ => None,
+      },
+      _ => None,
+    };
+
+    let mut query = QueryBuilder::new();
+
+    match id {
+      Some(id) if Self::DIALECT.supports_upsert() => {
+        query.add_segment("UPSERT").add_segment(record(self, &id))
+      }
+      Some(id) => query.add_segment("UPDATE").add_segment(record(self, &id)),
+      None => query.add_segment("CREATE").add_segment(self.to_string()),
+    };
+
+    let query = query
+      .content("$content")
+      .segment("RETURN AFTER")
+      .bind("content", content)?;
+
+    Ok(query.into_typed())
+  }
+
+  /// Build a `SELECT` query fetching the row with the given id.
+  fn find<'a>(&self, id: &str) -> TypedQuery<'a, T> {
+    let query = QueryBuilder::new().select("*").from(record(self, id));
+
+    match Self::SOFT_DELETE_FIELD {
+      Some(field) => query.filter(format!("{field} IS NONE")).into_typed(),
+      None => query.into_typed(),
+    }
+  }
+
+  /// Build a `SELECT` query fetching the single row with the given id part,
+  /// which is bound to the `$id` parameter alongside the table bound to `$tb`.
+  ///
+  /// # Example
+  /// ```
+  /// #![allow(incomplete_features)]
+  /// #![feature(generic_const_exprs)]
+  /// use serde::Deserialize;
+  /// use serde::Serialize;
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// model!(Account {
+  ///   pub handle,
+  /// });
+  ///
+  /// #[derive(Serialize, Deserialize)]
+  /// struct Account {
+  ///   handle: String,
+  /// }
+  ///
+  /// impl SurrealCrud<Account> for schema::Account<0> {}
+  ///
+  /// let (query, bindings) = schema::model.find_by_id("john").unwrap().build_with_bindings();
+  ///
+  /// assert_eq!("SELECT * FROM ONLY type::thing($tb, $id)", query);
+  /// assert_eq!(Some(&serde_json::json!("Account")), bindings.get("tb"));
+  /// assert_eq!(Some(&serde_json::json!("john")), bindings.get("id"));
+  /// ```
+  fn find_by_id<'a, I: Serialize>(&self, id: I) -> SqlSerializeResult<TypedQuery<'a, T>> {
+    let query = QueryBuilder::new()
+      .select("*")
+      .from("ONLY type::thing($tb, $id)");
+
+    let query = match Self::SOFT_DELETE_FIELD {
+      Some(field) => query.filter(format!("{field} IS NONE")),
+      None => query,
+    };
+
+    let query = query.bind("tb", self.table_name())?.bind("id", id)?;
+
+    Ok(query.into_typed())
+  }
+
+  /// Build a `SELECT` query fetching the first row matching the `condition`.
+  fn find_one<'a, C: Into<Cow<'a, str>>>(&self, condition: C) -> TypedQuery<'a, T> {
+    self
+      .find_where(condition)
+      .into_inner()
+      .limit(1)
+      .into_typed()
+  }
+
+  /// Build a `SELECT` query fetching the rows matching the `condition`.
+  fn find_where<'a, C: Into<Cow<'a, str>>>(&self, condition: C) -> TypedQuery<'a, T> {
+    QueryBuilder::new()
+      .select("*")
+      .from(self.to_string())
+      .filter(live_rows(Self::SOFT_DELETE_FIELD, condition))
+      .into_typed()
+  }
+
+  /// Build a query counting the rows matching the `condition`, the number can
+  /// be read with [`QueryBuilder::fetch_count()`].
+  fn count_where<'a, C: Into<Cow<'a, str>>>(&self, condition: C) -> QueryBuilder<'a> {
+    QueryBuilder::new()
+      .select("count()")
+      .from(self.to_string())
+      .filter(live_rows(Self::SOFT_DELETE_FIELD, condition))
+      .segment("GROUP ALL")
+  }
+
+  /// Build a query checking whether any row matches the `condition`, the result
+  /// can be read with [`QueryBuilder::fetch_exists()`].
+  fn exists_where<'a, C: Into<Cow<'a, str>>>(&self, condition: C) -> QueryBuilder<'a> {
+    QueryBuilder::new().exists(
+      self.to_string(),
+      live_rows(Self::SOFT_DELETE_FIELD, condition),
+    )
+  }
+
+  /// Build an `UPDATE` query merging the `patch` bound to the `$patch`
+  /// parameter into the row with the given id. The soft deleted rows are left
+  /// untouched, unless the query is built from [`SurrealCrud::with_deleted()`].
+  fn update<'a, P: Serialize>(&self, id: &str, patch: &P) -> SqlSerializeResult<TypedQuery<'a, T>> {
+    let query = QueryBuilder::new()
+      .update(record(self, id))
+      .segment("MERGE $patch");
+
+    let query = match Self::SOFT_DELETE_FIELD {
+      Some(field) => query.filter(format!("{field} IS NONE")),
+      None => query,
+    };
+
+    Ok(query.bind("patch", patch)?.into_typed())
+  }
+
+  /// Build a `DELETE` query removing the row with the given id, or an `UPDATE`
+  /// query setting its [`SurrealCrud::SOFT_DELETE_FIELD`] to the current time
+  /// when the soft deletion is enabled.
+  fn delete<'a>(&self, id: &str) -> QueryBuilder<'a> {
+    match Self::SOFT_DELETE_FIELD {
+      Some(field) => QueryBuilder::new()
+        .update(record(self, id))
+        .set(format!("{field} = time::now()")),
+      None => QueryBuilder::new().delete(record(self, id)),
+    }
+  }
+
+  /// Build the `DEFINE FIELD` statements maintaining the `created_at` and
+  /// `updated_at` fields of the rows, to run once when the table is defined.
+  /// The database sets the fields on every write, whether it is made by the
+  /// queries of this trait or not.
+  ///
+  /// # Example
+  /// ```
+  /// #![allow(incomplete_features)]
+  /// #![feature(generic_const_exprs)]
+  /// use serde::Deserialize;
+  /// use serde::Serialize;
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// model!(Account {
+  ///   pub handle,
+  ///   pub created_at,
+  ///   pub updated_at,
+  /// });
+  ///
+  /// #[derive(Serialize, Deserialize)]
+  /// struct Account {
+  ///   handle: String,
+  /// }
+  ///
+  /// impl SurrealCrud<Account> for schema::Account<0> {}
+  ///
+  /// assert_eq!(
+  ///   "DEFINE FIELD created_at ON TABLE Account VALUE $before OR time::now(); \
+  ///    DEFINE FIELD updated_at ON TABLE Account VALUE time::now();",
+  ///   schema::model.define_timestamps().build()
+  /// );
+  /// ```
+  fn define_timestamps<'a>(&self) -> QueryBuilder<'a> {
+    let table = self.to_string();
+    let mut query = QueryBuilder::new();
+
+    query
+      .add_segment(format!(
+        "DEFINE FIELD created_at ON TABLE {table} VALUE $before OR time::now();"
+      ))
+      .add_segment(format!(
+        "DEFINE FIELD updated_at ON TABLE {table} VALUE time::now();"
+      ));
+
+    query
+  }
+
+  /// Return a view of the model ignoring the soft deletion, whose queries also
+  /// read the deleted rows and whose [`SurrealCrud::delete()`] removes the rows
+  /// for good.
+  fn with_deleted(&self) -> WithDeleted<'_, Self>
+  where
+    Self: Sized,
+  {
+    WithDeleted { model: self }
+  }
+}
+
+/// A view of a model ignoring its soft deletion, see
+/// [`SurrealCrud::with_deleted()`].
+pub struct WithDeleted<'m, M> {
+  model: &'m M,
+}
+
+impl<M: Display> Display for WithDeleted<'_, M> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.model.fmt(f)
+  }
+}
+
+impl<M: SchemaModel> SchemaModel for WithDeleted<'_, M> {
+  fn field_names(&self) -> &'static [&'static str] {
+    self.model.field_names()
+  }
+
+  fn table_name(&self) -> &'static str {
+    self.model.table_name()
+  }
+
+  fn field_types(&self) -> &'static [(&'static str, &'static str)] {
+    self.model.field_types()
+  }
+
+  fn indexes(&self) -> &'static [SchemaIndex] {
+    self.model.indexes()
+  }
+}
+
+/// Every setting of the model is kept but the soft deletion field.
+impl<T, M> SurrealCrud<T> for WithDeleted<'_, M>
+where
+  T: Serialize + DeserializeOwned,
+  M: SurrealCrud<T>,
+{
+  const DIALECT: Dialect = M::DIALECT;
+}
+
+/// Return the condition restricted to the rows that are not soft deleted when
+/// a soft deletion `field` is set.
+fn live_rows<'a, C: Into<Cow<'a, str>>>(field: Option<&str>, condition: C) -> Cow<'a, str> {
+  match field {
+    Some(field) => Cow::Owned(format!("{field} IS NONE AND ({})", condition.into())),
+    None => condition.into(),
+  }
+}
+
+/// Return the record id of the row of the `model`'s table with the given id,
+/// the table of the ids that already start with it is not repeated. The table
+/// is matched unescaped and written escaped like the model writes it.
+fn record<M: SchemaModel + ?Sized>(model: &M, id: &str) -> String {
+  let table = model.table_name();
+  let id = id
+    .strip_prefix(table)
+    .and_then(|rest| rest.strip_prefix(':'))
+    .unwrap_or(id);
+
+  format!("{}:{}", escape_field(table), escape_ident(id))
+}
@@ -0,0 +1,82 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::model::SqlSerializeError;
+use crate::model::SqlSerializeResult;
+
+/// The direction a cursor walks through the rows, see
+/// [`QueryBuilder::after()`](crate::querybuilder::QueryBuilder::after).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+  /// Walk through the rows in ascending order, the next page holds the rows
+  /// greater than the cursor.
+  Asc,
+
+  /// Walk through the rows in descending order, the next page holds the rows
+  /// lower than the cursor.
+  Desc,
+}
+
+impl Direction {
+  /// The comparison operator selecting the rows past the cursor.
+  pub(crate) fn operator(self) -> &'static str {
+    match self {
+      Self::Asc => ">",
+      Self::Desc => "<",
+    }
+  }
+
+  /// The keyword ending the ORDER BY clause.
+  pub(crate) fn keyword(self) -> &'static str {
+    match self {
+      Self::Asc => "ASC",
+      Self::Desc => "DESC",
+    }
+  }
+}
+
+/// Return the cursor of the page following the given rows, which is the value
+/// of `field` in the last row. Nested fields are reached with dots, like
+/// `settings.theme`.
+///
+/// Returns `None` if there are no rows or if the last one has no such field,
+/// meaning there is no next page to fetch.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// #[derive(serde::Serialize)]
+/// struct Account {
+///   handle: String,
+///   created_at: u64,
+/// }
+///
+/// let rows = vec![
+///   Account { handle: "John".to_owned(), created_at: 10 },
+///   Account { handle: "Mark".to_owned(), created_at: 12 },
+/// ];
+///
+/// assert_eq!(Some(serde_json::json!(12)), next_cursor(&rows, "created_at").unwrap());
+/// assert_eq!(None, next_cursor(&Vec::<Account>::new(), "created_at").unwrap());
+/// ```
+pub fn next_cursor<T: Serialize>(rows: &[T], field: &str) -> SqlSerializeResult<Option<Value>> {
+  let Some(last) = rows.last() else {
+    return Ok(None);
+  };
+
+  let mut value =
+    serde_json::to_value(last).map_err(|e| SqlSerializeError::Message(e.to_string()))?;
+
+  for key in field.split('.') {
+    value = match value {
+      Value::Object(mut object) => match object.remove(key) {
+        Some(value) => value,
+        None => return Ok(None),
+      },
+      _ => return Ok(None),
+    };
+  }
+
+  Ok(Some(value).filter(|value| !value.is_null()))
+}
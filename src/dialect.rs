@@ -0,0 +1,41 @@
+use std::fmt::{self, Display};
+
+/// The version of SurrealDB the queries target, for the syntax that differs
+/// from one version to the other. See [`BuildOptions::dialect`](crate::querybuilder::BuildOptions::dialect)
+/// and [`SurrealCrud::DIALECT`](crate::crud::SurrealCrud::DIALECT).
+///
+/// The dialect is only enforced by
+/// [`QueryBuilder::try_build_with()`](crate::querybuilder::QueryBuilder::try_build_with),
+/// the other builds accept the syntax of any version.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+  /// SurrealDB 1.x, that has no `UPSERT` statement and no recursive graph
+  /// paths like `.{..3}->knows->?`.
+  V1,
+
+  /// SurrealDB 2.x, every syntax the crate emits is supported.
+  #[default]
+  V2,
+}
+
+impl Dialect {
+  /// Whether the `UPSERT` statement is available, in 1.x an `UPDATE` creates
+  /// the missing records instead.
+  pub fn supports_upsert(self) -> bool {
+    self != Self::V1
+  }
+
+  /// Whether the recursive graph paths like `.{..3}->knows->?` are available.
+  pub fn supports_recursive_paths(self) -> bool {
+    self != Self::V1
+  }
+}
+
+impl Display for Dialect {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Dialect::V1 => formatter.write_str("1.x"),
+      Dialect::V2 => formatter.write_str("2.x"),
+    }
+  }
+}
@@ -0,0 +1,342 @@
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::codegen::field_type;
+use crate::codegen::unescape;
+use crate::model::SchemaModel;
+use crate::model::SchemaRegistry;
+use crate::querybuilder::QueryBuilder;
+use crate::runner::QueryRunner;
+
+/// The differences found between a model and its table in the database.
+///
+/// The fields are compared by name, and by type when the model declares one
+/// like `handle: string`. The indexes are compared by name, fields and
+/// uniqueness when the drift is computed from a model with
+/// [`SchemaDrift::compare_model()`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SchemaDrift {
+  pub table: String,
+
+  /// The fields declared by the model but not defined on the table.
+  pub missing_fields: Vec<String>,
+
+  /// The fields defined on the table but not declared by the model.
+  pub unexpected_fields: Vec<String>,
+
+  /// The fields defined on the table with another type than the one the model
+  /// declares.
+  pub mismatched_types: Vec<FieldTypeDrift>,
+
+  /// The indexes declared by the model but not defined on the table.
+  pub missing_indexes: Vec<String>,
+
+  /// The indexes defined on the table over other fields, or with another
+  /// uniqueness, than the model declares.
+  pub mismatched_indexes: Vec<String>,
+
+  /// The indexes defined on the table but not declared by the model.
+  pub unexpected_indexes: Vec<String>,
+}
+
+/// A field defined with another type than the one its model declares.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldTypeDrift {
+  pub field: String,
+
+  /// The type declared by the model.
+  pub expected: String,
+
+  /// The type the field is defined with, `None` if it is defined without one.
+  pub found: Option<String>,
+}
+
+impl SchemaDrift {
+  /// Compare the `fields` of a model with the output of an `INFO FOR TABLE`
+  /// statement on its `table`, by name only. The `id` field is ignored as it is
+  /// never defined on the table, and so are the nested fields like
+  /// `settings.theme`.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let info = serde_json::json!({
+  ///   "events": {},
+  ///   "fields": {
+  ///     "handle": "DEFINE FIELD handle ON Account TYPE string",
+  ///     "phone": "DEFINE FIELD phone ON Account TYPE string"
+  ///   },
+  ///   "indexes": {}
+  /// });
+  ///
+  /// let drift = SchemaDrift::compare("Account", &["id", "handle", "email"], &info);
+  ///
+  /// assert_eq!(vec!["email"], drift.missing_fields);
+  /// assert_eq!(vec!["phone"], drift.unexpected_fields);
+  /// ```
+  pub fn compare(table: &str, fields: &[&str], info: &Value) -> Self {
+    let defined_fields = definitions(info, "fields", "fd");
+
+    Self {
+      table: table.to_owned(),
+      missing_fields: fields
+        .iter()
+        .filter(|field| **field != "id" && !defined_fields.contains_key(**field))
+        .map(|field| field.to_string())
+        .collect(),
+      unexpected_fields: defined_fields
+        .keys()
+        .filter(|field| !field.contains(['.', '[']) && !fields.contains(&field.as_str()))
+        .cloned()
+        .collect(),
+      ..Default::default()
+    }
+  }
+
+  /// Compare the fields, their types and the indexes of the model with the
+  /// output of an `INFO FOR TABLE` statement on its table. The types are
+  /// compared without their whitespaces and case.
+  ///
+  /// # Example
+  /// ```
+  /// #![allow(incomplete_features)]
+  /// #![feature(generic_const_exprs)]
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// model!(Account {
+  ///   id,
+  ///   pub handle: string,
+  ///   pub age: option<int>,
+  ///
+  ///   @unique account_handle(handle),
+  ///   @index account_age(age),
+  /// });
+  ///
+  /// let info = serde_json::json!({
+  ///   "fields": {
+  ///     "handle": "DEFINE FIELD handle ON Account TYPE string",
+  ///     "age": "DEFINE FIELD age ON Account TYPE int"
+  ///   },
+  ///   "indexes": {
+  ///     "account_handle": "DEFINE INDEX account_handle ON Account FIELDS handle"
+  ///   }
+  /// });
+  ///
+  /// let drift = SchemaDrift::compare_model(&schema::model, &info);
+  ///
+  /// assert_eq!("age", drift.mismatched_types[0].field);
+  /// assert_eq!(Some("int"), drift.mismatched_types[0].found.as_deref());
+  /// assert_eq!(vec!["account_age"], drift.missing_indexes);
+  /// assert_eq!(vec!["account_handle"], drift.mismatched_indexes);
+  /// ```
+  pub fn compare_model(model: &dyn SchemaModel, info: &Value) -> Self {
+    let mut drift = Self::compare(model.table_name(), model.field_names(), info);
+    let defined_fields = definitions(info, "fields", "fd");
+    let defined_indexes = definitions(info, "indexes", "ix");
+
+    for (field, expected) in model.field_types() {
+      let Some(definition) = defined_fields.get(*field) else {
+        continue;
+      };
+
+      let found = defined_type(definition);
+      let matches = found
+        .as_deref()
+        .is_some_and(|found| normalized(found) == normalized(expected));
+
+      if !matches {
+        drift.mismatched_types.push(FieldTypeDrift {
+          field: field.to_string(),
+          expected: expected.to_string(),
+          found,
+        });
+      }
+    }
+
+    for index in model.indexes() {
+      let Some(definition) = defined_indexes.get(index.name) else {
+        drift.missing_indexes.push(index.name.to_owned());
+        continue;
+      };
+
+      let (fields, is_unique) = defined_index(definition);
+
+      if fields != index.fields || is_unique != index.is_unique {
+        drift.mismatched_indexes.push(index.name.to_owned());
+      }
+    }
+
+    drift.unexpected_indexes = defined_indexes
+      .keys()
+      .filter(|name| !model.indexes().iter().any(|index| index.name == *name))
+      .cloned()
+      .collect();
+
+    drift
+  }
+
+  /// Return whether the model and its table match.
+  pub fn is_empty(&self) -> bool {
+    self.missing_fields.is_empty()
+      && self.unexpected_fields.is_empty()
+      && self.mismatched_types.is_empty()
+      && self.missing_indexes.is_empty()
+      && self.mismatched_indexes.is_empty()
+      && self.unexpected_indexes.is_empty()
+  }
+}
+
+/// Run an `INFO FOR TABLE` statement on the `table` through the supplied
+/// `runner` and compare its output with the `fields` of the model, see
+/// [`SchemaDrift::compare()`].
+///
+/// ```rs
+/// let drift = detect_schema_drift(&runner, &account.to_string(), account.field_names()).await?;
+///
+/// if !drift.is_empty() {
+///   panic!("the Account table drifted from its model: {drift:?}");
+/// }
+/// ```
+pub async fn detect_schema_drift<R: QueryRunner>(
+  runner: &R, table: &str, fields: &[&str],
+) -> Result<SchemaDrift, R::Error> {
+  let info = table_info(runner, table).await?;
+
+  Ok(SchemaDrift::compare(table, fields, &info))
+}
+
+/// Run an `INFO FOR TABLE` statement on the table of the `model` through the
+/// supplied `runner` and compare its output with the model, see
+/// [`SchemaDrift::compare_model()`].
+pub async fn detect_model_drift<R: QueryRunner>(
+  runner: &R, model: &dyn SchemaModel,
+) -> Result<SchemaDrift, R::Error> {
+  let info = table_info(runner, &model.to_string()).await?;
+
+  Ok(SchemaDrift::compare_model(model, &info))
+}
+
+/// Look for the drift of every model of the `registry`, the tables that match
+/// their model are left out.
+///
+/// ```rs
+/// let registry = SchemaRegistry::new().register(&account).register(&project);
+/// let drifts = detect_registry_drift(&runner, &registry).await?;
+///
+/// if !drifts.is_empty() {
+///   panic!("the schema drifted from the models: {drifts:?}");
+/// }
+/// ```
+pub async fn detect_registry_drift<R: QueryRunner>(
+  runner: &R, registry: &SchemaRegistry<'_>,
+) -> Result<Vec<SchemaDrift>, R::Error> {
+  let mut drifts = Vec::new();
+
+  for model in registry.models() {
+    let drift = detect_model_drift(runner, *model).await?;
+
+    if !drift.is_empty() {
+      drifts.push(drift);
+    }
+  }
+
+  Ok(drifts)
+}
+
+async fn table_info<R: QueryRunner>(runner: &R, table: &str) -> Result<Value, R::Error> {
+  let info: Option<Value> = QueryBuilder::new()
+    .segment("INFO FOR TABLE")
+    .segment(table)
+    .fetch_one(runner)
+    .await?;
+
+  Ok(info.unwrap_or(Value::Null))
+}
+
+/// Return the definitions in the output of an `INFO FOR TABLE` statement under
+/// the `key`, or the `short_key` of the older versions, by name.
+fn definitions(info: &Value, key: &str, short_key: &str) -> Map<String, Value> {
+  let Value::Object(info) = info else {
+    return Map::new();
+  };
+
+  match info.get(key).or_else(|| info.get(short_key)) {
+    Some(Value::Object(definitions)) => definitions.clone(),
+    _ => Map::new(),
+  }
+}
+
+/// Return the type following the TYPE keyword of a `DEFINE FIELD` statement.
+fn defined_type(definition: &Value) -> Option<String> {
+  let words = definition_words(definition);
+  let on = words
+    .iter()
+    .position(|word| word.eq_ignore_ascii_case("ON"))?;
+  let rest = match words.get(on + 1) {
+    Some(word) if word.eq_ignore_ascii_case("TABLE") => &words[on + 2..],
+    _ => &words[on + 1..],
+  };
+
+  // the table is skipped so a table named `type` is not taken for the keyword
+  let rest = rest.get(1..)?;
+  let index = rest
+    .iter()
+    .position(|word| word.eq_ignore_ascii_case("TYPE"))?;
+
+  Some(field_type(&rest[index + 1..]))
+}
+
+/// Return the fields and the uniqueness of a `DEFINE INDEX` statement.
+fn defined_index(definition: &Value) -> (Vec<&str>, bool) {
+  let words = definition_words(definition);
+  let is_keyword = |word: &str, keywords: &[&str]| {
+    keywords
+      .iter()
+      .any(|keyword| word.eq_ignore_ascii_case(keyword))
+  };
+
+  let fields = match words
+    .iter()
+    .position(|word| is_keyword(word, &["FIELDS", "COLUMNS"]))
+  {
+    Some(index) => words[index + 1..]
+      .iter()
+      .take_while(|word| !is_keyword(word, INDEX_KEYWORDS))
+      .flat_map(|word| word.split(','))
+      .map(|field| unescape(field.trim()))
+      .filter(|field| !field.is_empty())
+      .collect(),
+    None => Vec::new(),
+  };
+
+  let is_unique = words.iter().any(|word| is_keyword(word, &["UNIQUE"]));
+
+  (fields, is_unique)
+}
+
+fn definition_words(definition: &Value) -> Vec<&str> {
+  match definition {
+    Value::String(definition) => definition.split_whitespace().collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// The keywords that end the list of fields of a `DEFINE INDEX` statement.
+const INDEX_KEYWORDS: &[&str] = &[
+  "UNIQUE",
+  "SEARCH",
+  "MTREE",
+  "HNSW",
+  "COMMENT",
+  "CONCURRENTLY",
+];
+
+/// Return the type without its whitespaces and case, to compare it.
+fn normalized(sql_type: &str) -> String {
+  sql_type
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .flat_map(char::to_lowercase)
+    .collect()
+}
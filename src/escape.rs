@@ -0,0 +1,146 @@
+use std::borrow::Cow;
+
+/// Escape the given identifier with `⟨` and `⟩` if it contains anything else
+/// than ASCII letters, digits and underscores, for example spaces, dashes,
+/// colons or unicode characters. A `⟩` in the identifier is escaped as well.
+///
+/// The identifiers that are already escaped (with `⟨⟩` or backticks), the
+/// parameters (`$id`), the complex ids (`[...]` and `{...}`) and the id
+/// generators (`rand()`) are returned as-is.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// assert_eq!("John", escape_ident("John"));
+/// assert_eq!("⟨John Doe⟩", escape_ident("John Doe"));
+/// assert_eq!("⟨jöhn⟩", escape_ident("jöhn"));
+/// assert_eq!("⟨a\\⟩b⟩", escape_ident("a⟩b"));
+/// assert_eq!("$id", escape_ident("$id"));
+/// ```
+pub fn escape_ident(ident: &str) -> Cow<'_, str> {
+  match needs_escaping(ident) {
+    true => Cow::Owned(format!("⟨{}⟩", ident.replace('⟩', "\\⟩"))),
+    false => Cow::Borrowed(ident),
+  }
+}
+
+/// The SurrealQL keywords that make a query ambiguous when they are used as bare
+/// field or table names, like a field named `value` or `order`. They are
+/// compared without case.
+pub const RESERVED_WORDS: &[&str] = &[
+  "AND", "AS", "ASC", "BEGIN", "BY", "CANCEL", "COMMIT", "CONTAINS", "CONTENT", "CREATE", "DEFINE",
+  "DELETE", "DESC", "ELSE", "END", "EXPLAIN", "FALSE", "FETCH", "FROM", "GROUP", "IF", "INSERT",
+  "INSIDE", "INTO", "IS", "LET", "LIMIT", "MERGE", "NONE", "NOT", "NULL", "OMIT", "ON", "ONLY",
+  "OR", "ORDER", "OUTSIDE", "PARALLEL", "PATCH", "RELATE", "REMOVE", "RETURN", "SELECT", "SET",
+  "SPLIT", "START", "THEN", "TIMEOUT", "TRUE", "UPDATE", "UPSERT", "VALUE", "WHERE", "WITH",
+];
+
+/// Return whether the word is one of the [RESERVED_WORDS], no matter its case.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// assert!(is_reserved_word("order"));
+/// assert!(!is_reserved_word("handle"));
+/// ```
+pub fn is_reserved_word(word: &str) -> bool {
+  RESERVED_WORDS
+    .iter()
+    .any(|reserved| reserved.eq_ignore_ascii_case(word))
+}
+
+/// Escape the given field or table name like [escape_ident] does, and also when
+/// it is one of the [RESERVED_WORDS]. The names the [`model!()`](crate::model)
+/// macro writes in the queries go through it.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// assert_eq!("handle", escape_field("handle"));
+/// assert_eq!("⟨value⟩", escape_field("value"));
+/// assert_eq!("⟨Order⟩", escape_field("Order"));
+/// assert_eq!("⟨prénom⟩", escape_field("prénom"));
+/// ```
+pub fn escape_field(name: &str) -> Cow<'_, str> {
+  match is_reserved_word(name) {
+    true => Cow::Owned(format!("⟨{name}⟩")),
+    false => escape_ident(name),
+  }
+}
+
+/// Surround the value with single quotes to inline it as a SurrealQL string,
+/// escaping the quotes, the backslashes, the line breaks and the other control
+/// characters it may contain. The rest of the unicode characters are kept as
+/// they are.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// assert_eq!("'John'", escape_str("John"));
+/// assert_eq!(r"'it\'s'", escape_str("it's"));
+/// assert_eq!(r"'C:\\dir'", escape_str(r"C:\dir"));
+/// assert_eq!(r"'one\ntwo'", escape_str("one\ntwo"));
+/// assert_eq!(r"'\u0007jöhn'", escape_str("\u{7}jöhn"));
+/// ```
+pub fn escape_str(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len() + 2);
+  escaped.push('\'');
+
+  for c in value.chars() {
+    match c {
+      '\'' => escaped.push_str("\\'"),
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      '\t' => escaped.push_str("\\t"),
+      c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+      c => escaped.push(c),
+    };
+  }
+
+  escaped.push('\'');
+  escaped
+}
+
+/// Escape the id part of a `table:id` record id using [escape_ident]. Anything
+/// that is not a record id, like an edge, a traversal or a field path, is
+/// returned as-is.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// assert_eq!("Account:⟨John Doe⟩", escape_record_id("Account:John Doe"));
+/// assert_eq!("Account:John", escape_record_id("Account:John"));
+/// assert_eq!("->manage->Project", escape_record_id("->manage->Project"));
+/// ```
+pub fn escape_record_id(record: &str) -> Cow<'_, str> {
+  let is_traversal =
+    record.starts_with("->") || record.starts_with("<-") || record.starts_with('.');
+
+  match record.split_once(':') {
+    Some((table, id)) if !is_traversal && is_simple_ident(table) && needs_escaping(id) => {
+      Cow::Owned(format!("{table}:{}", escape_ident(id)))
+    }
+    _ => Cow::Borrowed(record),
+  }
+}
+
+/// Return whether the identifier is only made of ASCII letters, digits and
+/// underscores.
+fn is_simple_ident(ident: &str) -> bool {
+  !ident.is_empty() && ident.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn needs_escaping(ident: &str) -> bool {
+  let is_escaped = (ident.starts_with('⟨') && ident.ends_with('⟩'))
+    || (ident.len() > 1 && ident.starts_with('`') && ident.ends_with('`'));
+  let is_complex = ident.starts_with('[') || ident.starts_with('{') || ident.starts_with('$');
+  let is_generator = ident.strip_suffix("()").is_some_and(is_simple_ident);
+
+  !is_simple_ident(ident) && !is_escaped && !is_complex && !is_generator
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use super::ForeignKey;
+use super::ForeignVec;
+
+/// Implemented by the containers of ForeignKeys, no matter how they are nested,
+/// to iterate over the keys and loaded values they hold without unwrapping
+/// every layer by hand.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let links: HashMap<String, Vec<Option<Foreign<u32>>>> = serde_json::from_str(
+///   r#"{ "authors": ["Account:John", null, 42] }"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(2, links.foreigns().count());
+/// assert_eq!(vec!["Account:John"], links.foreign_keys().collect::<Vec<_>>());
+/// assert_eq!(vec![&42], links.loaded_values().collect::<Vec<_>>());
+/// ```
+pub trait ForeignContainer<V, K> {
+  /// Iterate over every ForeignKey in the container.
+  fn foreigns<'a>(&'a self) -> impl Iterator<Item = &'a ForeignKey<V, K>>
+  where
+    V: 'a,
+    K: 'a;
+
+  /// Iterate over the keys of the ForeignKeys in the container, the ones without
+  /// a key are skipped.
+  fn foreign_keys<'a>(&'a self) -> impl Iterator<Item = &'a K>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self.foreigns().filter_map(ForeignKey::key)
+  }
+
+  /// Iterate over the loaded values of the ForeignKeys in the container, the
+  /// ones without a loaded value are skipped.
+  fn loaded_values<'a>(&'a self) -> impl Iterator<Item = &'a V>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self.foreigns().filter_map(ForeignKey::value)
+  }
+
+  /// Iterate over the keys of the ForeignKeys in the container that are not
+  /// loaded yet, to fetch them all in a single query.
+  fn missing_keys<'a>(&'a self) -> impl Iterator<Item = &'a K>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self
+      .foreigns()
+      .filter(|foreign| !foreign.is_loaded())
+      .filter_map(ForeignKey::key)
+  }
+
+  /// Split the container into its loaded values and the keys that are not loaded
+  /// yet.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let releases: Vec<Foreign<u32>> = vec![
+  ///   Foreign::new_value(1),
+  ///   Foreign::new_key("Release:two".to_owned()),
+  ///   Foreign::new_loaded("Release:three".to_owned(), 3),
+  /// ];
+  ///
+  /// let (loaded, missing) = releases.split_loaded();
+  /// assert_eq!(vec![&1, &3], loaded);
+  /// assert_eq!(vec!["Release:two"], missing);
+  /// ```
+  fn split_loaded<'a>(&'a self) -> (Vec<&'a V>, Vec<&'a K>)
+  where
+    V: 'a,
+    K: 'a,
+  {
+    (
+      self.loaded_values().collect(),
+      self.missing_keys().collect(),
+    )
+  }
+}
+
+impl<V, K> ForeignContainer<V, K> for ForeignKey<V, K> {
+  fn foreigns<'a>(&'a self) -> impl Iterator<Item = &'a ForeignKey<V, K>>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    std::iter::once(self)
+  }
+}
+
+impl<V, K> ForeignContainer<V, K> for ForeignVec<V, K> {
+  fn foreigns<'a>(&'a self) -> impl Iterator<Item = &'a ForeignKey<V, K>>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self.iter()
+  }
+}
+
+impl<V, K, C> ForeignContainer<V, K> for Option<C>
+where
+  C: ForeignContainer<V, K>,
+{
+  fn foreigns<'a>(&'a self) -> impl Iterator<Item = &'a ForeignKey<V, K>>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self.iter().flat_map(C::foreigns)
+  }
+}
+
+impl<V, K, C> ForeignContainer<V, K> for Vec<C>
+where
+  C: ForeignContainer<V, K>,
+{
+  fn foreigns<'a>(&'a self) -> impl Iterator<Item = &'a ForeignKey<V, K>>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self.as_slice().foreigns()
+  }
+}
+
+impl<V, K, C> ForeignContainer<V, K> for [C]
+where
+  C: ForeignContainer<V, K>,
+{
+  fn foreigns<'a>(&'a self) -> impl Iterator<Item = &'a ForeignKey<V, K>>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self.iter().flat_map(C::foreigns)
+  }
+}
+
+impl<V, K, Q, C, S> ForeignContainer<V, K> for HashMap<Q, C, S>
+where
+  C: ForeignContainer<V, K>,
+{
+  fn foreigns<'a>(&'a self) -> impl Iterator<Item = &'a ForeignKey<V, K>>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self.values().flat_map(C::foreigns)
+  }
+}
+
+impl<V, K, Q, C> ForeignContainer<V, K> for BTreeMap<Q, C>
+where
+  C: ForeignContainer<V, K>,
+{
+  fn foreigns<'a>(&'a self) -> impl Iterator<Item = &'a ForeignKey<V, K>>
+  where
+    V: 'a,
+    K: 'a,
+  {
+    self.values().flat_map(C::foreigns)
+  }
+}
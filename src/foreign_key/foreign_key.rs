@@ -1,11 +1,17 @@
 use std::cell::Cell;
+use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::future::Future;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::marker::PhantomData;
 use std::ops::Deref;
-use std::ops::DerefMut;
 
+use serde::de::Visitor;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::DepthScope;
 use super::IntoKey;
 use super::KeySerializeControl;
 use super::LoadedValue;
@@ -18,6 +24,7 @@ use super::LoadedValue;
 ///  - Loaded data,
 ///  - An ID,
 ///  - None of the above (`null`)
+///  - Explicitly none, see [`ForeignKey::new_null()`]
 ///
 /// When a field is set as a `ForeignKey<V, K>` or a `Foreign<V>`, the field will
 /// always be serialized into an ID so you can be sure you won't get raw data
@@ -73,82 +80,430 @@ use super::LoadedValue;
 ///
 /// There are cases where this behaviour is not what you wish to happen, calling
 /// [`ForeignKey::allow_value_serialize()`] flags the ForeignKey to serialize any
-/// potential value it may hold.
+/// potential value it may hold. Alternatively, serializing a type through the
+/// [WithLoadedValues](super::WithLoadedValues) wrapper serializes the loaded values
+/// of every ForeignKey it contains for that single call, and the
+/// [WithLoadedDepth](super::WithLoadedDepth) wrapper does the same up to a maximum
+/// depth of nested ForeignKeys.
+///
+/// # Retained keys
+/// A ForeignKey can hold both its key and its loaded value at the same time, see
+/// [`ForeignKey::new_loaded()`]. In that case [`ForeignKey::key()`] still returns
+/// the original key after the value was loaded, and the key is serialized as-is
+/// instead of being derived again from the value with [IntoKey].
+///
+/// # Unloaded and null
+/// An unloaded ForeignKey means the field was not fetched, while a null
+/// ForeignKey means the link was explicitly cleared. Both serialize into `null`
+/// but only the latter is reported by [`ForeignKey::is_null()`], which allows
+/// partial updates to leave unloaded fields untouched while still clearing the
+/// null ones:
+/// ```rs
+/// struct FileUpdate {
+///   #[serde(skip_serializing_if = "ForeignKey::is_unloaded")]
+///   author: Foreign<User>
+/// }
+/// ```
+///
+/// Note that a `null` value is deserialized as unloaded, a null ForeignKey can
+/// only be created from code.
+///
+/// # Missing fields
+/// A ForeignKey field that is missing from the deserialized data is unloaded,
+/// just like an `Option` field would be `None`, so there is no need for a
+/// `#[serde(default)]` attribute on the field.
 ///
 /// **Note** that if you plan to use `ForeignKey<T, String>` (where the second generic
 /// type is a string), you can use the `Foreign<T>` type in the same module to
 /// shorten the declaration.
-#[derive(Deserialize)]
-#[serde(from = "LoadedValue<V, K>")]
 pub struct ForeignKey<V, K> {
-  inner: LoadedValue<V, K>,
-
-  #[serde(skip)]
+  value: Option<V>,
+  key: Option<K>,
+  null: bool,
   allow_value_serialize: Cell<bool>,
 }
 
 impl<V, K> Default for ForeignKey<V, K> {
   fn default() -> Self {
     Self {
-      inner: Default::default(),
+      value: None,
+      key: None,
+      null: false,
       allow_value_serialize: Cell::new(false),
     }
   }
 }
 
-impl<V, K> Deref for ForeignKey<V, K> {
-  type Target = LoadedValue<V, K>;
-
-  fn deref(&self) -> &Self::Target {
-    &self.inner
-  }
-}
-
-impl<V, K> DerefMut for ForeignKey<V, K> {
-  fn deref_mut(&mut self) -> &mut Self::Target {
-    &mut self.inner
-  }
-}
-
 impl<V, K> ForeignKey<V, K> {
   pub fn new_value(value: V) -> Self {
     Self {
-      inner: LoadedValue::Loaded(value),
+      value: Some(value),
       ..Default::default()
     }
   }
 
   pub fn new_key(key: K) -> Self {
     Self {
-      inner: LoadedValue::Key(key),
+      key: Some(key),
+      ..Default::default()
+    }
+  }
+
+  /// Create a ForeignKey that holds both the loaded value and the key it was
+  /// loaded from, so the key remains accessible through [`ForeignKey::key()`].
+  pub fn new_loaded(key: K, value: V) -> Self {
+    Self {
+      value: Some(value),
+      key: Some(key),
       ..Default::default()
     }
   }
 
   pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create a ForeignKey that is explicitly set to none, as opposed to an
+  /// unloaded ForeignKey whose data simply wasn't fetched.
+  pub fn new_null() -> Self {
     Self {
-      inner: LoadedValue::Unloaded,
+      null: true,
       ..Default::default()
     }
   }
+
+  /// Access the inner value by checking if it is loaded or not, thus returning
+  /// an `Option<&T>` that is `Some` if it is loaded and `None` if it isn't.
+  pub fn value(&self) -> Option<&V> {
+    self.value.as_ref()
+  }
+
+  /// Access the inner key, thus returning a `Some<&I>` if the foreign key is
+  /// holding a key and `None` if it isn't.
+  ///
+  /// A loaded ForeignKey that retained its key still returns it.
+  pub fn key(&self) -> Option<&K> {
+    self.key.as_ref()
+  }
+
+  /// Return whether the current ForeignKey is unloaded. Returns `false` if `self`
+  /// is either a key, a loaded value or explicitly null.
+  pub fn is_unloaded(&self) -> bool {
+    self.value.is_none() && self.key.is_none() && !self.null
+  }
+
+  /// Return whether the current ForeignKey was explicitly set to none using
+  /// [`ForeignKey::new_null()`] or [`ForeignKey::set_null()`].
+  pub fn is_null(&self) -> bool {
+    self.null
+  }
+
+  /// Return whether the current ForeignKey holds a loaded value, whether it
+  /// retained its key or not.
+  pub fn is_loaded(&self) -> bool {
+    self.value.is_some()
+  }
+
+  /// Return whether the current ForeignKey only holds a key, without any loaded
+  /// value.
+  pub fn is_key(&self) -> bool {
+    self.value.is_none() && self.key.is_some()
+  }
+
+  /// Map the loaded value, if any, to another type while keeping the key as-is.
+  ///
+  /// The combinators of the ForeignKey are named like the [Option] ones and are
+  /// called before the methods reached through its [Deref] to the value.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let name: Foreign<String> = Foreign::new_value("John".to_owned());
+  /// let length = name.map(|name| name.len());
+  ///
+  /// assert_eq!(Some(&4), length.value());
+  /// ```
+  pub fn map<U, F>(self, f: F) -> ForeignKey<U, K>
+  where
+    F: FnOnce(V) -> U,
+  {
+    ForeignKey {
+      value: self.value.map(f),
+      key: self.key,
+      null: self.null,
+      allow_value_serialize: self.allow_value_serialize,
+    }
+  }
+
+  /// Convert from `&ForeignKey<V, K>` to `ForeignKey<&V, &K>`. It is not named
+  /// `as_ref` so it does not shadow [`Option::as_ref()`] and [AsRef].
+  pub fn as_refs(&self) -> ForeignKey<&V, &K> {
+    ForeignKey {
+      value: self.value.as_ref(),
+      key: self.key.as_ref(),
+      null: self.null,
+      allow_value_serialize: self.allow_value_serialize.clone(),
+    }
+  }
+
+  /// Take the data out of the ForeignKey, leaving it unloaded in its place.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let mut author: Foreign<String> = Foreign::new_key("Account:John".to_owned());
+  /// let taken = author.take();
+  ///
+  /// assert!(author.is_unloaded());
+  /// assert!(taken.is_key());
+  /// ```
+  pub fn take(&mut self) -> Self {
+    std::mem::take(self)
+  }
+
+  /// Return the loaded value, or `default` if it isn't loaded.
+  pub fn unwrap_or(self, default: V) -> V {
+    self.value.unwrap_or(default)
+  }
+
+  /// Return the loaded value, or the default value of `V` if it isn't loaded.
+  pub fn unwrap_or_default(self) -> V
+  where
+    V: Default,
+  {
+    self.value.unwrap_or_default()
+  }
+
+  /// Drop any data `self` may currently hold and set it to the loaded state
+  /// with the given value.
+  pub fn set_value(&mut self, value: V) {
+    self.value = Some(value);
+    self.key = None;
+    self.null = false;
+  }
+
+  /// Set the loaded value while retaining the current key, if any. Useful to
+  /// upgrade a key to its loaded value after it was fetched manually.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let mut author: Foreign<String> = Foreign::new_key("Account:John".to_owned());
+  /// author.set_loaded("John".to_owned());
+  ///
+  /// assert_eq!(Some(&"John".to_owned()), author.value());
+  /// assert_eq!(Some(&"Account:John".to_owned()), author.key());
+  /// ```
+  pub fn set_loaded(&mut self, value: V) {
+    self.value = Some(value);
+    self.null = false;
+  }
+
+  /// Merge the data of `other` into `self`: the value and key `other` holds
+  /// replace the ones of `self`, while the ones it is missing are kept. For
+  /// example merging a loaded value into a key results in a loaded ForeignKey
+  /// that retained its key.
+  pub fn merge(&mut self, other: Self) {
+    if other.null {
+      self.set_null();
+      return;
+    }
+
+    if let Some(value) = other.value {
+      self.set_loaded(value);
+    }
+
+    if let Some(key) = other.key {
+      self.key = Some(key);
+      self.null = false;
+    }
+  }
+
+  /// Drop any data `self` may currently hold and set it to the key state
+  /// with the given identifier.
+  pub fn set_key(&mut self, identifier: K) {
+    self.value = None;
+    self.key = Some(identifier);
+    self.null = false;
+  }
+
+  /// Drop the currently held value and key and set `self` to the unloaded state.
+  pub fn unload(&mut self) {
+    self.value = None;
+    self.key = None;
+    self.null = false;
+  }
+
+  /// Drop the currently held value and key and explicitly set `self` to none.
+  pub fn set_null(&mut self) {
+    self.value = None;
+    self.key = None;
+    self.null = true;
+  }
 }
 
 impl<V, K> ForeignKey<V, K>
 where
   V: IntoKey<K>,
 {
+  /// Return the key the ForeignKey points to, whatever its state is. The
+  /// retained key is returned if there is one, otherwise the key is obtained
+  /// from the loaded value with [IntoKey]. Returns `None` if `self` is unloaded.
+  /// Unlike [`ForeignKey::to_key()`] the loaded value is left in place.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// struct Account {
+  ///   id: String,
+  /// }
+  ///
+  /// impl IntoKey<String> for Account {
+  ///   fn into_key<E>(&self) -> Result<String, E>
+  ///   where
+  ///     E: serde::ser::Error,
+  ///   {
+  ///     Ok(self.id.clone())
+  ///   }
+  /// }
+  ///
+  /// let author: Foreign<Account> = Foreign::new_value(Account {
+  ///   id: "Account:John".to_owned(),
+  /// });
+  /// let key: SqlSerializeResult<Option<String>> = author.resolve_key();
+  ///
+  /// assert_eq!(Some("Account:John".to_owned()), key.unwrap());
+  /// ```
+  pub fn resolve_key<E>(&self) -> Result<Option<K>, E>
+  where
+    E: serde::ser::Error,
+    K: Clone,
+  {
+    match (&self.value, &self.key) {
+      (_, Some(key)) => Ok(Some(key.clone())),
+      (Some(value), None) => value.into_key().map(Some),
+      (None, None) => Ok(None),
+    }
+  }
+
+  /// Drop the loaded value and only keep its key. The retained key is used if
+  /// there is one, otherwise the key is obtained from the value with [IntoKey].
   pub fn to_key<E>(&mut self) -> Result<(), E>
   where
     E: serde::ser::Error,
   {
-    if let Some(value) = self.value() {
-      self.inner.set_key(value.into_key()?);
+    if let (Some(value), None) = (&self.value, &self.key) {
+      self.key = Some(value.into_key()?);
     }
 
+    self.value = None;
+
     Ok(())
   }
 }
 
+impl<V, K> ForeignKey<V, K> {
+  /// Lazily load the foreign node: if `self` only holds a key then `fetch` is
+  /// called with it and the fetched value is stored alongside the key. If `self`
+  /// is already loaded the value is returned without calling `fetch`, and if it
+  /// is unloaded `None` is returned.
+  ///
+  /// The crate does not depend on any database client, `fetch` is where the
+  /// record is actually queried using the client of your choice.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  /// # use std::future::Future;
+  /// # fn block_on<F: Future>(future: F) -> F::Output {
+  /// #   let mut future = std::pin::pin!(future);
+  /// #   let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+  /// #   loop {
+  /// #     if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+  /// #       return output;
+  /// #     }
+  /// #   }
+  /// # }
+  ///
+  /// let mut author: Foreign<String> = Foreign::new_key("Account:John".to_owned());
+  ///
+  /// let loaded = block_on(author.load_with(|key| {
+  ///   let name = key.replace("Account:", "");
+  ///
+  ///   async move { Ok::<_, SqlSerializeError>(name) }
+  /// }));
+  ///
+  /// assert_eq!(Some(&"John".to_owned()), loaded.unwrap());
+  /// assert_eq!(Some(&"Account:John".to_owned()), author.key());
+  /// ```
+  pub async fn load_with<F, Fut, E>(&mut self, fetch: F) -> Result<Option<&V>, E>
+  where
+    F: FnOnce(&K) -> Fut,
+    Fut: Future<Output = Result<V, E>>,
+  {
+    if self.value.is_none() {
+      if let Some(key) = &self.key {
+        self.value = Some(fetch(key).await?);
+      }
+    }
+
+    Ok(self.value.as_ref())
+  }
+}
+
+/// A ForeignKey dereferences to its loaded value as an `Option<V>`, so it can be
+/// used like one in everyday code. The mutations go through the ForeignKey
+/// methods to keep its key consistent.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let author: Foreign<String> = Foreign::new_value("John".to_owned());
+///
+/// assert_eq!(Some("John"), author.as_deref());
+///
+/// if let Some(name) = &*author {
+///   assert_eq!("John", name);
+/// }
+/// ```
+impl<V, K> Deref for ForeignKey<V, K> {
+  type Target = Option<V>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.value
+  }
+}
+
+impl<V, K> AsRef<Option<V>> for ForeignKey<V, K> {
+  fn as_ref(&self) -> &Option<V> {
+    &self.value
+  }
+}
+
+/// Helpers for boxed values, as needed by self-referential models where the
+/// foreign type is the type holding the ForeignKey.
+impl<V, K> ForeignKey<Box<V>, K> {
+  /// Create a ForeignKey holding the given value in a box.
+  pub fn new_boxed(value: V) -> Self {
+    Self::new_value(Box::new(value))
+  }
+
+  /// Access the loaded value without its box, see [`ForeignKey::value()`].
+  pub fn value_unboxed(&self) -> Option<&V> {
+    self.value.as_deref()
+  }
+
+  /// Move the loaded value, if any, out of its box.
+  pub fn unboxed(self) -> ForeignKey<V, K> {
+    self.map(|value| *value)
+  }
+}
+
 impl<V, K> KeySerializeControl for ForeignKey<V, K> {
   fn allow_value_serialize(&self) {
     self.allow_value_serialize.replace(true);
@@ -169,17 +524,174 @@ where
   where
     S: serde::Serializer,
   {
-    match (&self.inner, self.allow_value_serialize.take()) {
-      (LoadedValue::Loaded(v), false) => v.into_key()?.serialize(serializer),
-      (inner, _) => inner.serialize(serializer),
+    let allow_value_serialize = self.allow_value_serialize.take();
+
+    match (&self.value, &self.key) {
+      (Some(v), k) => {
+        // the scope must be held while the value is serialized so any nested
+        // ForeignKey sees the remaining depth.
+        let scope = DepthScope::enter_loaded_value();
+
+        match (k, allow_value_serialize || scope.is_some()) {
+          (_, true) => v.serialize(serializer),
+          (Some(k), false) => k.serialize(serializer),
+          (None, false) => v.into_key()?.serialize(serializer),
+        }
+      }
+      (None, Some(k)) => k.serialize(serializer),
+      (None, None) => Option::<K>::None.serialize(serializer),
+    }
+  }
+}
+
+/// What a ForeignKey is compared and hashed by: its key whenever it has one,
+/// and only its loaded value otherwise.
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Identity<'a, V, K> {
+  Unloaded,
+  Null,
+  Key(&'a K),
+  Value(&'a V),
+}
+
+impl<V, K> ForeignKey<V, K> {
+  fn identity(&self) -> Identity<'_, V, K> {
+    match (&self.value, &self.key) {
+      (_, Some(key)) => Identity::Key(key),
+      (Some(value), None) => Identity::Value(value),
+      (None, None) if self.null => Identity::Null,
+      (None, None) => Identity::Unloaded,
     }
   }
 }
 
+/// Two ForeignKeys are equal if they point to the same key, whether they are
+/// loaded or not. ForeignKeys without keys are compared by their loaded values.
+impl<V, K> PartialEq for ForeignKey<V, K>
+where
+  V: PartialEq,
+  K: PartialEq,
+{
+  fn eq(&self, other: &Self) -> bool {
+    self.identity() == other.identity()
+  }
+}
+
+impl<V, K> Eq for ForeignKey<V, K>
+where
+  V: Eq,
+  K: Eq,
+{
+}
+
+impl<V, K> Hash for ForeignKey<V, K>
+where
+  V: Hash,
+  K: Hash,
+{
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.identity().hash(state);
+  }
+}
+
+impl<V, K> PartialOrd for ForeignKey<V, K>
+where
+  V: PartialOrd,
+  K: PartialOrd,
+{
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    self.identity().partial_cmp(&other.identity())
+  }
+}
+
+impl<V, K> Ord for ForeignKey<V, K>
+where
+  V: Ord,
+  K: Ord,
+{
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.identity().cmp(&other.identity())
+  }
+}
+
+/// Deserializes through `deserialize_option` so that `null` as well as missing
+/// fields result in an unloaded ForeignKey, the rest is deserialized as a
+/// [LoadedValue].
+impl<'de, V, K> Deserialize<'de> for ForeignKey<V, K>
+where
+  V: Deserialize<'de>,
+  K: Deserialize<'de>,
+{
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    struct ForeignKeyVisitor<V, K>(PhantomData<(V, K)>);
+
+    impl<'de, V, K> Visitor<'de> for ForeignKeyVisitor<V, K>
+    where
+      V: Deserialize<'de>,
+      K: Deserialize<'de>,
+    {
+      type Value = ForeignKey<V, K>;
+
+      fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a loaded value, a key or null")
+      }
+
+      fn visit_none<E>(self) -> Result<Self::Value, E>
+      where
+        E: serde::de::Error,
+      {
+        Ok(ForeignKey::new())
+      }
+
+      fn visit_unit<E>(self) -> Result<Self::Value, E>
+      where
+        E: serde::de::Error,
+      {
+        Ok(ForeignKey::new())
+      }
+
+      fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+      where
+        D: serde::Deserializer<'de>,
+      {
+        LoadedValue::deserialize(deserializer).map(ForeignKey::from)
+      }
+    }
+
+    deserializer.deserialize_option(ForeignKeyVisitor(PhantomData))
+  }
+}
+
 impl<V, K> From<LoadedValue<V, K>> for ForeignKey<V, K> {
   fn from(value: LoadedValue<V, K>) -> Self {
+    match value {
+      LoadedValue::Loaded(value) => Self::new_value(value),
+      LoadedValue::Key(key) => Self::new_key(key),
+      LoadedValue::Unloaded => Self::new(),
+    }
+  }
+}
+
+/// Convert a value into a loaded ForeignKey.
+///
+/// **Note** that there is no `From<K>` conversion for keys as it would conflict
+/// with this one whenever `V` and `K` are the same type, use
+/// [`ForeignKey::new_key()`] instead or a [TypedKey](super::TypedKey).
+impl<V, K> From<V> for ForeignKey<V, K> {
+  fn from(value: V) -> Self {
+    Self::new_value(value)
+  }
+}
+
+/// Convert an optional value into a loaded ForeignKey, or an unloaded one if it
+/// is `None`.
+impl<V, K> From<Option<V>> for ForeignKey<V, K> {
+  fn from(value: Option<V>) -> Self {
     Self {
-      inner: value,
+      value,
       ..Default::default()
     }
   }
@@ -191,6 +703,12 @@ where
   K: Debug,
 {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    self.inner.fmt(f)
+    match (&self.value, &self.key) {
+      (Some(value), Some(key)) => f.debug_tuple("Loaded").field(value).field(key).finish(),
+      (Some(value), None) => f.debug_tuple("Loaded").field(value).finish(),
+      (None, Some(key)) => f.debug_tuple("Key").field(key).finish(),
+      (None, None) if self.null => write!(f, "Null"),
+      (None, None) => write!(f, "Unloaded"),
+    }
   }
 }
@@ -0,0 +1,177 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::ForeignKey;
+use super::IntoKey;
+use super::KeySerializeControl;
+
+/// Represents a Foreign field of a PATCH-style payload, distinguishing the field
+/// that is absent from the field that is present with a key or a value, and the
+/// field that is present as `null`.
+///
+/// A [ForeignKey] deserializes a `null` and a missing field the same way, which
+/// is fine for complete nodes but loses the intent of a partial update where an
+/// absent field must be left untouched while a `null` one must clear the link.
+///
+/// The field must be declared with `#[serde(default)]` so a missing field
+/// deserializes into [`ForeignPatch::Absent`], and with
+/// `#[serde(skip_serializing_if = "ForeignPatch::is_absent")]` so the payload can
+/// be passed as is to a `MERGE` clause without touching the absent fields.
+///
+/// # Example
+/// ```
+/// use serde::Deserialize;
+/// use serde::Serialize;
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct User {
+///   id: String,
+/// }
+///
+/// impl IntoKey<String> for User {
+///   fn into_key<E: serde::ser::Error>(&self) -> Result<String, E> {
+///     Ok(self.id.clone())
+///   }
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct FilePatch {
+///   #[serde(default, skip_serializing_if = "ForeignPatch::is_absent")]
+///   author: ForeignPatch<User>,
+///
+///   #[serde(default, skip_serializing_if = "ForeignPatch::is_absent")]
+///   reviewer: ForeignPatch<User>,
+///
+///   #[serde(default, skip_serializing_if = "ForeignPatch::is_absent")]
+///   folder: ForeignPatch<User>,
+/// }
+///
+/// let patch: FilePatch = serde_json::from_str(r#"{ "author": "User:john", "reviewer": null }"#).unwrap();
+///
+/// assert_eq!(Some("User:john"), patch.author.foreign().and_then(|author| author.key()).map(String::as_str));
+/// assert!(patch.reviewer.is_null());
+/// assert!(patch.folder.is_absent());
+///
+/// assert_eq!(
+///   r#"{"author":"User:john","reviewer":null}"#,
+///   serde_json::to_string(&patch).unwrap()
+/// );
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub enum ForeignPatch<V, K = String> {
+  /// The field is absent from the payload and must be left untouched.
+  #[default]
+  Absent,
+
+  /// The field is present as `null` and the link must be cleared.
+  Null,
+
+  /// The field is present with a key or a value.
+  Set(ForeignKey<V, K>),
+}
+
+impl<V, K> ForeignPatch<V, K> {
+  /// Return whether the field is absent from the payload.
+  pub fn is_absent(&self) -> bool {
+    matches!(self, Self::Absent)
+  }
+
+  /// Return whether the field is present as `null`.
+  pub fn is_null(&self) -> bool {
+    matches!(self, Self::Null)
+  }
+
+  /// Return whether the field is present with a key or a value.
+  pub fn is_set(&self) -> bool {
+    matches!(self, Self::Set(_))
+  }
+
+  /// Access the inner ForeignKey if the field is present with a key or a value.
+  pub fn foreign(&self) -> Option<&ForeignKey<V, K>> {
+    match self {
+      Self::Set(foreign) => Some(foreign),
+      _ => None,
+    }
+  }
+
+  /// Consume the patch and return the nested options, the outer one is `None`
+  /// when the field is absent and the inner one is `None` when it is `null`.
+  pub fn into_option(self) -> Option<Option<ForeignKey<V, K>>> {
+    match self {
+      Self::Absent => None,
+      Self::Null => Some(None),
+      Self::Set(foreign) => Some(Some(foreign)),
+    }
+  }
+}
+
+impl<V, K> From<ForeignKey<V, K>> for ForeignPatch<V, K> {
+  fn from(foreign: ForeignKey<V, K>) -> Self {
+    Self::Set(foreign)
+  }
+}
+
+impl<V, K> From<Option<Option<ForeignKey<V, K>>>> for ForeignPatch<V, K> {
+  fn from(patch: Option<Option<ForeignKey<V, K>>>) -> Self {
+    match patch {
+      None => Self::Absent,
+      Some(None) => Self::Null,
+      Some(Some(foreign)) => Self::Set(foreign),
+    }
+  }
+}
+
+impl<V, K> KeySerializeControl for ForeignPatch<V, K> {
+  fn allow_value_serialize(&self) {
+    if let Self::Set(foreign) = self {
+      foreign.allow_value_serialize();
+    }
+  }
+
+  fn disallow_value_serialize(&self) {
+    if let Self::Set(foreign) = self {
+      foreign.disallow_value_serialize();
+    }
+  }
+}
+
+/// Both the absent and the null patches serialize into `null`, the absent ones
+/// are expected to be skipped with `skip_serializing_if`.
+impl<V, K> Serialize for ForeignPatch<V, K>
+where
+  V: IntoKey<K>,
+  K: Serialize,
+  V: Serialize,
+{
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    match self {
+      Self::Absent | Self::Null => serializer.serialize_none(),
+      Self::Set(foreign) => foreign.serialize(serializer),
+    }
+  }
+}
+
+/// A `null` deserializes into [`ForeignPatch::Null`] and the rest into
+/// [`ForeignPatch::Set`], the missing fields rely on `#[serde(default)]` to be
+/// [`ForeignPatch::Absent`].
+impl<'de, V, K> Deserialize<'de> for ForeignPatch<V, K>
+where
+  V: Deserialize<'de>,
+  K: Deserialize<'de>,
+{
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    Ok(
+      match Option::<ForeignKey<V, K>>::deserialize(deserializer)? {
+        Some(foreign) => Self::Set(foreign),
+        None => Self::Null,
+      },
+    )
+  }
+}
@@ -0,0 +1,176 @@
+use std::fmt::Debug;
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::Foreign;
+use super::IntoKey;
+use super::KeySerializeControl;
+
+/// Represents a collection of foreign nodes, where every element may
+/// independently be loaded, a key, or unloaded.
+///
+/// Unlike a `Foreign<Vec<T>>` that is either entirely loaded or entirely made of
+/// keys, a `ForeignVec<T>` can be deserialized from an array of keys, an array
+/// of loaded objects, or a mix of both:
+/// ```json
+/// ["Project:one", { "id": "Project:two", "name": "two" }]
+/// ```
+///
+/// A `null` or missing value deserializes into an empty collection.
+///
+/// Just like [Foreign], the key type defaults to a `String` but can be replaced
+/// with a strongly-typed record id using the second generic parameter.
+///
+/// The collection dereferences to a `Vec<Foreign<T>>` so every usual vector
+/// method is available, and it offers the [`ForeignVec::keys()`] and
+/// [`ForeignVec::loaded()`] views to only iterate over the elements in a given
+/// state.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let mut projects: ForeignVec<String> = ForeignVec::new_key(vec!["Project:one".to_owned()]);
+/// projects.push(Foreign::new_value("two".to_owned()));
+///
+/// assert_eq!(vec!["Project:one"], projects.keys().collect::<Vec<_>>());
+/// assert_eq!(vec!["two"], projects.loaded().collect::<Vec<_>>());
+/// assert_eq!(2, projects.iter().count());
+/// ```
+#[derive(Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(from = "Option<Vec<Foreign<V, K>>>")]
+pub struct ForeignVec<V, K = String> {
+  inner: Vec<Foreign<V, K>>,
+}
+
+impl<V, K> Default for ForeignVec<V, K> {
+  fn default() -> Self {
+    Self { inner: Vec::new() }
+  }
+}
+
+impl<V, K> Deref for ForeignVec<V, K> {
+  type Target = Vec<Foreign<V, K>>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
+impl<V, K> DerefMut for ForeignVec<V, K> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.inner
+  }
+}
+
+impl<V, K> ForeignVec<V, K> {
+  /// Create an empty collection.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create a collection where every element is a loaded value.
+  pub fn new_value(values: Vec<V>) -> Self {
+    values.into_iter().map(Foreign::new_value).collect()
+  }
+
+  /// Create a collection where every element is a key.
+  pub fn new_key(keys: Vec<K>) -> Self {
+    keys.into_iter().map(Foreign::new_key).collect()
+  }
+
+  /// Iterate over the keys of the elements that are currently holding a key,
+  /// including the loaded elements that retained their key. The other elements
+  /// are skipped.
+  pub fn keys(&self) -> impl Iterator<Item = &K> {
+    self.inner.iter().filter_map(|foreign| foreign.key())
+  }
+
+  /// Iterate over the values of the elements that are currently loaded, the
+  /// keys and unloaded elements are skipped.
+  pub fn loaded(&self) -> impl Iterator<Item = &V> {
+    self.inner.iter().filter_map(|foreign| foreign.value())
+  }
+
+  /// Consume the collection and return the inner vector.
+  pub fn into_inner(self) -> Vec<Foreign<V, K>> {
+    self.inner
+  }
+}
+
+impl<V, K> From<Vec<Foreign<V, K>>> for ForeignVec<V, K> {
+  fn from(inner: Vec<Foreign<V, K>>) -> Self {
+    Self { inner }
+  }
+}
+
+impl<V, K> From<Option<Vec<Foreign<V, K>>>> for ForeignVec<V, K> {
+  fn from(inner: Option<Vec<Foreign<V, K>>>) -> Self {
+    Self {
+      inner: inner.unwrap_or_default(),
+    }
+  }
+}
+
+impl<V, K> FromIterator<Foreign<V, K>> for ForeignVec<V, K> {
+  fn from_iter<I: IntoIterator<Item = Foreign<V, K>>>(iter: I) -> Self {
+    Self {
+      inner: iter.into_iter().collect(),
+    }
+  }
+}
+
+impl<V, K> IntoIterator for ForeignVec<V, K> {
+  type Item = Foreign<V, K>;
+  type IntoIter = std::vec::IntoIter<Foreign<V, K>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.inner.into_iter()
+  }
+}
+
+impl<'a, V, K> IntoIterator for &'a ForeignVec<V, K> {
+  type Item = &'a Foreign<V, K>;
+  type IntoIter = std::slice::Iter<'a, Foreign<V, K>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.inner.iter()
+  }
+}
+
+impl<V, K> KeySerializeControl for ForeignVec<V, K> {
+  fn allow_value_serialize(&self) {
+    self.inner.allow_value_serialize();
+  }
+
+  fn disallow_value_serialize(&self) {
+    self.inner.disallow_value_serialize();
+  }
+}
+
+impl<V, K> Serialize for ForeignVec<V, K>
+where
+  V: IntoKey<K>,
+  K: Serialize,
+  V: Serialize,
+{
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    self.inner.serialize(serializer)
+  }
+}
+
+impl<V, K> Debug for ForeignVec<V, K>
+where
+  V: Debug,
+  K: Debug,
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.inner.fmt(f)
+  }
+}
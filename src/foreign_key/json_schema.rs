@@ -0,0 +1,106 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::schema::SchemaObject;
+use schemars::schema::SubschemaValidation;
+use schemars::JsonSchema;
+
+use super::ForeignKey;
+use super::ForeignPatch;
+use super::ForeignVec;
+use super::RecordId;
+use super::TypedKey;
+
+/// A ForeignKey is either its key, `null` when it is unloaded, or the loaded
+/// value. The schema is inlined in the schema of the fields so the key and value
+/// of each link are visible where the link is used.
+///
+/// ```rs
+/// #[derive(JsonSchema)]
+/// struct File {
+///   name: String,
+///   author: Foreign<Account>,
+/// }
+/// ```
+///
+/// The `author` field is `{ "anyOf": [{ "type": ["string", "null"] }, { "$ref":
+/// "#/definitions/Account" }] }`.
+impl<V: JsonSchema, K: JsonSchema> JsonSchema for ForeignKey<V, K> {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    format!("Foreign_{}_or_{}", K::schema_name(), V::schema_name())
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    let key = gen.subschema_for::<Option<K>>();
+    let value = gen.subschema_for::<V>();
+
+    Schema::Object(SchemaObject {
+      subschemas: Some(Box::new(SubschemaValidation {
+        any_of: Some(vec![key, value]),
+        ..Default::default()
+      })),
+      ..Default::default()
+    })
+  }
+}
+
+impl<V: JsonSchema, K: JsonSchema> JsonSchema for ForeignVec<V, K> {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    format!("Array_of_{}", ForeignKey::<V, K>::schema_name())
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    Vec::<ForeignKey<V, K>>::json_schema(gen)
+  }
+}
+
+/// Both the absent and the null patches are `null`, like an unloaded link.
+impl<V: JsonSchema, K: JsonSchema> JsonSchema for ForeignPatch<V, K> {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    ForeignKey::<V, K>::schema_name()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    ForeignKey::<V, K>::json_schema(gen)
+  }
+}
+
+/// A record id is written as its `table:id` string.
+impl JsonSchema for RecordId {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    "RecordId".to_owned()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    String::json_schema(gen)
+  }
+}
+
+impl<T> JsonSchema for TypedKey<T> {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    "TypedKey".to_owned()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    String::json_schema(gen)
+  }
+}
@@ -1,17 +1,39 @@
+mod foreign_container;
 mod foreign_key;
+mod foreign_patch;
+mod foreign_vec;
 mod into_key;
+#[cfg(feature = "schemars")]
+mod json_schema;
 mod key_ser_control;
 mod loaded_value;
+mod record_id;
+#[cfg(feature = "client")]
+mod thing;
+mod typed_key;
+mod with_loaded_values;
 
 use loaded_value::*;
+use with_loaded_values::DepthScope;
 
+pub use foreign_container::*;
 pub use foreign_key::*;
+pub use foreign_patch::*;
+pub use foreign_vec::*;
 pub use into_key::*;
 pub use key_ser_control::*;
+pub use record_id::*;
+pub use typed_key::*;
+pub use with_loaded_values::WithLoadedDepth;
+pub use with_loaded_values::WithLoadedValues;
 
 /// A `ForeignKey` whose `Key` type is set to a `String` by default.
-pub type Foreign<T> = ForeignKey<T, String>;
+///
+/// The key type can be swapped for any strongly-typed record id, for example
+/// `Foreign<User, UserId>`, in which case the field serializes using the key
+/// type's own `Serialize` implementation.
+pub type Foreign<T, K = String> = ForeignKey<T, K>;
 
-/// A `ForeignKey` whose `Key` type is set to a `Vec<String>` by default, and whose
-/// `Value` type is set to be a `Vec<T>`
-pub type ForeignVec<T> = ForeignKey<Vec<T>, Vec<String>>;
+/// A `ForeignKey` whose keys are validated to belong to the table of `T`, see
+/// [TypedKey].
+pub type TypedForeign<T> = ForeignKey<T, TypedKey<T>>;
@@ -0,0 +1,134 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::Table;
+use super::TypedKey;
+use crate::escape::escape_ident;
+use crate::literal::SqlLiteral;
+
+/// A pointer to a record of the database, made of the `table` it belongs to and
+/// its `id`.
+///
+/// It is displayed and serialized in the `table:id` format, the id being escaped
+/// if needed, and it can be parsed back from that same format. It can be used as
+/// the key type of a [Foreign](super::Foreign) or passed to the
+/// [QueryBuilder](crate::querybuilder::QueryBuilder) methods.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let john = RecordId::new("Account", "John Doe");
+/// assert_eq!("Account:⟨John Doe⟩", john.to_string());
+/// assert_eq!(john, "Account:⟨John Doe⟩".parse().unwrap());
+///
+/// let query = QueryBuilder::new().select("*").from(&john).build();
+/// assert_eq!("SELECT * FROM Account:⟨John Doe⟩", query);
+///
+/// let author: Foreign<u32, RecordId> = serde_json::from_str("\"Account:John\"").unwrap();
+/// assert_eq!(Some(&RecordId::new("Account", "John")), author.key());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct RecordId {
+  table: String,
+  id: String,
+}
+
+impl RecordId {
+  pub fn new(table: impl Into<String>, id: impl Into<String>) -> Self {
+    Self {
+      table: table.into(),
+      id: id.into(),
+    }
+  }
+
+  /// Return the table the record belongs to.
+  pub fn table(&self) -> &str {
+    &self.table
+  }
+
+  /// Return the id of the record, without the table prefix and unescaped.
+  pub fn id(&self) -> &str {
+    &self.id
+  }
+}
+
+impl Display for RecordId {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}:{}", self.table, escape_ident(&self.id))
+  }
+}
+
+/// The record id is written as is, without quotes, so it is compared as a record
+/// rather than as a string.
+impl SqlLiteral for RecordId {
+  fn to_sql_literal(&self) -> String {
+    self.to_string()
+  }
+}
+
+impl FromStr for RecordId {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.split_once(':') {
+      Some((table, id)) if !table.is_empty() && !id.is_empty() => {
+        let id = match id.strip_prefix('⟨').and_then(|id| id.strip_suffix('⟩')) {
+          Some(escaped) => escaped.replace("\\⟩", "⟩"),
+          None => id.to_owned(),
+        };
+
+        Ok(Self::new(table, id))
+      }
+      _ => Err(format!("`{s}` is not a record id in the `table:id` format")),
+    }
+  }
+}
+
+impl Serialize for RecordId {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for RecordId {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    let key = String::deserialize(deserializer)?;
+
+    key.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+impl<T: Table> From<TypedKey<T>> for RecordId {
+  fn from(key: TypedKey<T>) -> Self {
+    Self::new(T::TABLE, key.id())
+  }
+}
+
+impl From<RecordId> for String {
+  fn from(record: RecordId) -> Self {
+    record.to_string()
+  }
+}
+
+impl<'a> From<RecordId> for Cow<'a, str> {
+  fn from(record: RecordId) -> Self {
+    Cow::Owned(record.to_string())
+  }
+}
+
+impl<'a> From<&RecordId> for Cow<'a, str> {
+  fn from(record: &RecordId) -> Self {
+    Cow::Owned(record.to_string())
+  }
+}
@@ -0,0 +1,56 @@
+use surrealdb::sql::Id;
+use surrealdb::sql::Thing;
+
+use super::RecordId;
+use super::Table;
+use super::TypedKey;
+use crate::literal::SqlLiteral;
+use crate::model::SqlSerializeError;
+
+/// The raw id of the thing is kept, a string id made of digits only is read back
+/// as a number id since a [RecordId] does not tell them apart.
+impl From<Thing> for RecordId {
+  fn from(thing: Thing) -> Self {
+    Self::new(thing.tb, thing.id.to_raw())
+  }
+}
+
+impl From<&Thing> for RecordId {
+  fn from(thing: &Thing) -> Self {
+    Self::new(thing.tb.clone(), thing.id.to_raw())
+  }
+}
+
+/// The record is parsed like SurrealDB does, the ids it cannot parse are kept as
+/// string ids.
+impl From<RecordId> for Thing {
+  fn from(record: RecordId) -> Self {
+    Thing::try_from(record.to_string().as_str()).unwrap_or_else(|_| {
+      Thing::from((
+        record.table().to_owned(),
+        Id::String(record.id().to_owned()),
+      ))
+    })
+  }
+}
+
+impl<T: Table> TryFrom<Thing> for TypedKey<T> {
+  type Error = SqlSerializeError;
+
+  fn try_from(thing: Thing) -> Result<Self, Self::Error> {
+    Self::new(RecordId::from(thing).to_string())
+  }
+}
+
+impl<T: Table> From<TypedKey<T>> for Thing {
+  fn from(key: TypedKey<T>) -> Self {
+    RecordId::from(key).into()
+  }
+}
+
+/// The thing is written as a record id, like a [RecordId] is.
+impl SqlLiteral for Thing {
+  fn to_sql_literal(&self) -> String {
+    RecordId::from(self).to_sql_literal()
+  }
+}
@@ -0,0 +1,199 @@
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::ForeignKey;
+use crate::escape::escape_ident;
+use crate::literal::SqlLiteral;
+use crate::model::SqlSerializeError;
+use crate::model::SqlSerializeResult;
+
+/// Types that are stored in a table of the database, it allows a [TypedKey] to
+/// know which table prefix its keys must have. The schemas of the
+/// [`model!()`](crate::model) macro implement it with their table name.
+pub trait Table {
+  /// The name of the table, as found in the keys before the `:`.
+  const TABLE: &'static str;
+}
+
+/// A key to a node of the `T` table, in the `table:id` format.
+///
+/// A TypedKey is validated when it is created and when it is deserialized, a key
+/// that belongs to another table (e.g `Project:one` when an `Account` key is
+/// expected) is rejected so that data bugs are caught early.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// struct Account;
+///
+/// impl Table for Account {
+///   const TABLE: &'static str = "Account";
+/// }
+///
+/// let key: TypedKey<Account> = serde_json::from_str("\"Account:John\"").unwrap();
+/// assert_eq!("John", key.id());
+///
+/// let key: Result<TypedKey<Account>, _> = serde_json::from_str("\"Project:one\"");
+/// assert!(key.is_err());
+/// ```
+pub struct TypedKey<T> {
+  key: String,
+  table: PhantomData<fn() -> T>,
+}
+
+impl<T: Table> TypedKey<T> {
+  /// Create a key after confirming it belongs to the `T` table and has an id.
+  pub fn new(key: impl Into<String>) -> SqlSerializeResult<Self> {
+    Self::validated(key.into()).map_err(SqlSerializeError::Message)
+  }
+
+  /// Create a key from the id part only, the table prefix is added
+  /// automatically and the id is escaped if needed, like a [RecordId] is.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// struct Account;
+  ///
+  /// impl Table for Account {
+  ///   const TABLE: &'static str = "Account";
+  /// }
+  ///
+  /// let key = TypedKey::<Account>::from_id("John Doe");
+  /// assert_eq!("Account:⟨John Doe⟩", key.as_str());
+  /// assert_eq!("John Doe", key.id());
+  /// ```
+  pub fn from_id(id: &str) -> Self {
+    Self {
+      key: format!("{}:{}", T::TABLE, escape_ident(id)),
+      table: PhantomData,
+    }
+  }
+
+  fn validated(key: String) -> Result<Self, String> {
+    let id = key
+      .strip_prefix(T::TABLE)
+      .and_then(|rest| rest.strip_prefix(':'));
+
+    match id {
+      Some("") => Err(format!("the key `{key}` has no id")),
+      Some(_) => Ok(Self {
+        key,
+        table: PhantomData,
+      }),
+      None => Err(format!(
+        "the key `{key}` does not belong to the `{}` table",
+        T::TABLE
+      )),
+    }
+  }
+
+  /// Return the id part of the key, without the table prefix and unescaped like
+  /// the id [TypedKey::from_id] takes.
+  pub fn id(&self) -> Cow<'_, str> {
+    let id = &self.key[T::TABLE.len() + 1..];
+
+    match id.strip_prefix('⟨').and_then(|id| id.strip_suffix('⟩')) {
+      Some(escaped) => Cow::Owned(escaped.replace("\\⟩", "⟩")),
+      None => Cow::Borrowed(id),
+    }
+  }
+}
+
+impl<T> TypedKey<T> {
+  /// Return the complete key, table prefix included.
+  pub fn as_str(&self) -> &str {
+    &self.key
+  }
+
+  pub fn into_inner(self) -> String {
+    self.key
+  }
+}
+
+impl<T> SqlLiteral for TypedKey<T> {
+  fn to_sql_literal(&self) -> String {
+    self.key.clone()
+  }
+}
+
+impl<T> Clone for TypedKey<T> {
+  fn clone(&self) -> Self {
+    Self {
+      key: self.key.clone(),
+      table: PhantomData,
+    }
+  }
+}
+
+impl<T> PartialEq for TypedKey<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.key == other.key
+  }
+}
+
+impl<T> Eq for TypedKey<T> {}
+
+impl<T> Hash for TypedKey<T> {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    self.key.hash(state);
+  }
+}
+
+impl<T> PartialOrd for TypedKey<T> {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<T> Ord for TypedKey<T> {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    self.key.cmp(&other.key)
+  }
+}
+
+impl<T> Display for TypedKey<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.key)
+  }
+}
+
+impl<T> Debug for TypedKey<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    Debug::fmt(&self.key, f)
+  }
+}
+
+impl<T> Serialize for TypedKey<T> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    self.key.serialize(serializer)
+  }
+}
+
+impl<'de, T: Table> Deserialize<'de> for TypedKey<T> {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    let key = String::deserialize(deserializer)?;
+
+    Self::validated(key).map_err(serde::de::Error::custom)
+  }
+}
+
+impl<T> From<TypedKey<T>> for ForeignKey<T, TypedKey<T>> {
+  fn from(key: TypedKey<T>) -> Self {
+    Self::new_key(key)
+  }
+}
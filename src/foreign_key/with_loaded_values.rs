@@ -0,0 +1,172 @@
+use std::cell::Cell;
+
+use serde::Serialize;
+
+thread_local! {
+  /// The number of nested levels of loaded values that can still be serialized
+  /// on this thread, `None` when no wrapper is being serialized.
+  static LOADED_VALUES_DEPTH: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Restores the previous depth once dropped, even if the serialization panics.
+pub(crate) struct DepthScope(Option<usize>);
+
+impl DepthScope {
+  fn set(depth: Option<usize>) -> Self {
+    Self(LOADED_VALUES_DEPTH.with(|cell| cell.replace(depth)))
+  }
+
+  /// Enter one level of loaded values if a wrapper is currently being serialized
+  /// and its depth is not exhausted yet. Returns `None` if the value should be
+  /// serialized as a key instead.
+  pub(crate) fn enter_loaded_value() -> Option<Self> {
+    match LOADED_VALUES_DEPTH.with(Cell::get) {
+      Some(depth) if depth > 0 => Some(Self::set(Some(depth - 1))),
+      _ => None,
+    }
+  }
+}
+
+impl Drop for DepthScope {
+  fn drop(&mut self) {
+    LOADED_VALUES_DEPTH.with(|cell| cell.set(self.0));
+  }
+}
+
+/// Wraps any serializable type so that every [ForeignKey](super::ForeignKey) it
+/// contains, no matter how deeply nested, serializes its loaded value instead of
+/// its key during this serialization call.
+///
+/// This is the per-call counterpart of
+/// [`KeySerializeControl::allow_value_serialize()`](super::KeySerializeControl::allow_value_serialize):
+/// serializing the wrapped type directly keeps the default key-only behaviour
+/// that is right for database writes, while serializing it through the wrapper
+/// returns the full objects, for example in API responses.
+///
+/// # Example
+/// ```
+/// use serde::Serialize;
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// #[derive(Serialize)]
+/// struct Account {
+///   id: String,
+/// }
+///
+/// impl IntoKey<String> for Account {
+///   fn into_key<E>(&self) -> Result<String, E>
+///   where
+///     E: serde::ser::Error,
+///   {
+///     Ok(self.id.clone())
+///   }
+/// }
+///
+/// #[derive(Serialize)]
+/// struct File {
+///   author: Foreign<Account>,
+/// }
+///
+/// let file = File {
+///   author: Foreign::new_value(Account {
+///     id: "Account:John".to_owned(),
+///   }),
+/// };
+///
+/// assert_eq!(
+///   r#"{"author":"Account:John"}"#,
+///   serde_json::to_string(&file).unwrap()
+/// );
+///
+/// assert_eq!(
+///   r#"{"author":{"id":"Account:John"}}"#,
+///   serde_json::to_string(&WithLoadedValues(&file)).unwrap()
+/// );
+/// ```
+pub struct WithLoadedValues<T>(pub T);
+
+impl<T> Serialize for WithLoadedValues<T>
+where
+  T: Serialize,
+{
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let _scope = DepthScope::set(Some(usize::MAX));
+
+    self.0.serialize(serializer)
+  }
+}
+
+/// Like [WithLoadedValues] but only serializes the loaded values up to `depth`
+/// levels of nested ForeignKeys, any loaded value that is deeper is serialized as
+/// a key instead. A depth of `0` serializes every ForeignKey as a key.
+///
+/// This is useful for self-referential models like an `Account` with a
+/// `friend: Foreign<Box<Account>>` field, where the loaded friends may go
+/// arbitrarily deep.
+///
+/// # Example
+/// ```
+/// use serde::Serialize;
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// #[derive(Serialize)]
+/// struct Account {
+///   id: String,
+///   friend: Foreign<Box<Account>>,
+/// }
+///
+/// impl IntoKey<String> for Account {
+///   fn into_key<E>(&self) -> Result<String, E>
+///   where
+///     E: serde::ser::Error,
+///   {
+///     Ok(self.id.clone())
+///   }
+/// }
+///
+/// let john = Account {
+///   id: "Account:John".to_owned(),
+///   friend: Foreign::new_boxed(Account {
+///     id: "Account:Mark".to_owned(),
+///     friend: Foreign::new_boxed(Account {
+///       id: "Account:Ryan".to_owned(),
+///       friend: Foreign::new(),
+///     }),
+///   }),
+/// };
+///
+/// assert_eq!(
+///   serde_json::json!({
+///     "id": "Account:John",
+///     "friend": { "id": "Account:Mark", "friend": "Account:Ryan" }
+///   }),
+///   serde_json::to_value(WithLoadedDepth::new(&john, 1)).unwrap()
+/// );
+/// ```
+pub struct WithLoadedDepth<T> {
+  value: T,
+  depth: usize,
+}
+
+impl<T> WithLoadedDepth<T> {
+  pub fn new(value: T, depth: usize) -> Self {
+    Self { value, depth }
+  }
+}
+
+impl<T> Serialize for WithLoadedDepth<T>
+where
+  T: Serialize,
+{
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let _scope = DepthScope::set(Some(self.depth));
+
+    self.value.serialize(serializer)
+  }
+}
@@ -0,0 +1,174 @@
+use serde_json::Value;
+
+use crate::build_error::QueryBuilderResult;
+use crate::cursor::Direction;
+use crate::querybuilder::check_page;
+use crate::querybuilder::parameter_name;
+use crate::querybuilder::QueryBuilder;
+
+/// A reusable piece of query, like a pagination or a filter, that injects its
+/// clauses into a [QueryBuilder]. Handlers can accept a set of fragments and
+/// apply them all with [`QueryBuilder::apply()`].
+///
+/// The fragments are implemented for the options, vectors and tuples of
+/// fragments, which inject the fragments they hold in order.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// fn search<'a>(fragments: impl QueryFragment<'a>) -> String {
+///   QueryBuilder::new()
+///     .select("*")
+///     .from("Account")
+///     .apply(fragments)
+///     .build()
+/// }
+///
+/// let verified = Some(FieldFilter::new("verified", true));
+/// let query = search((
+///   verified,
+///   OrderBy::new("handle", Direction::Asc),
+///   Pagination::new(2, 10).unwrap(),
+/// ));
+///
+/// assert_eq!(
+///   "SELECT * FROM Account WHERE verified = $verified ORDER BY handle ASC LIMIT 10 START AT 10",
+///   query
+/// );
+/// ```
+pub trait QueryFragment<'a> {
+  /// Add the clauses of the fragment to the `query`.
+  fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a>;
+}
+
+impl<'a> QueryBuilder<'a> {
+  /// Inject the clauses of the `fragment` into the query. See [QueryFragment].
+  pub fn apply<F: QueryFragment<'a>>(self, fragment: F) -> Self {
+    fragment.inject(self)
+  }
+}
+
+impl<'a, F: QueryFragment<'a>> QueryFragment<'a> for Option<F> {
+  fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+    match self {
+      Some(fragment) => fragment.inject(query),
+      None => query,
+    }
+  }
+}
+
+impl<'a, F: QueryFragment<'a>> QueryFragment<'a> for Vec<F> {
+  fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+    self
+      .into_iter()
+      .fold(query, |query, fragment| fragment.inject(query))
+  }
+}
+
+impl<'a> QueryFragment<'a> for QueryBuilder<'a> {
+  fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+    query.extend(self)
+  }
+}
+
+macro_rules! impl_tuple_fragment {
+  ($($fragment:ident),+) => {
+    impl<'a, $($fragment: QueryFragment<'a>),+> QueryFragment<'a> for ($($fragment,)+) {
+      #[allow(non_snake_case)]
+      fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+        let ($($fragment,)+) = self;
+        $(let query = $fragment.inject(query);)+
+
+        query
+      }
+    }
+  };
+}
+
+impl_tuple_fragment!(A);
+impl_tuple_fragment!(A, B);
+impl_tuple_fragment!(A, B, C);
+impl_tuple_fragment!(A, B, C, D);
+impl_tuple_fragment!(A, B, C, D, E);
+impl_tuple_fragment!(A, B, C, D, E, F);
+
+/// Selects a page of rows, see [`QueryBuilder::paginate()`]. The page is
+/// checked when the fragment is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+  page: u64,
+  per_page: u64,
+}
+
+impl Pagination {
+  pub fn new(page: u64, per_page: u64) -> QueryBuilderResult<Self> {
+    check_page(page, per_page)?;
+
+    Ok(Self { page, per_page })
+  }
+
+  pub fn page(&self) -> u64 {
+    self.page
+  }
+
+  pub fn per_page(&self) -> u64 {
+    self.per_page
+  }
+}
+
+impl<'a> QueryFragment<'a> for Pagination {
+  fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+    query.add_page(self.page, self.per_page)
+  }
+}
+
+/// Orders the rows by a field in the given direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderBy<'a> {
+  pub field: &'a str,
+  pub direction: Direction,
+}
+
+impl<'a> OrderBy<'a> {
+  pub fn new(field: &'a str, direction: Direction) -> Self {
+    Self { field, direction }
+  }
+}
+
+impl<'a> QueryFragment<'a> for OrderBy<'a> {
+  fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+    match self.direction {
+      Direction::Asc => query.order_by_asc(self.field),
+      Direction::Desc => query.order_by_desc(self.field),
+    }
+  }
+}
+
+/// Keeps the rows whose field equals the value, which is bound to a parameter
+/// named after the field. The condition starts the WHERE clause of the
+/// statement, or is grouped and joined to it with an AND if it already exists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldFilter<'a> {
+  pub field: &'a str,
+  pub value: Value,
+}
+
+impl<'a> FieldFilter<'a> {
+  pub fn new<T: Into<Value>>(field: &'a str, value: T) -> Self {
+    Self {
+      field,
+      value: value.into(),
+    }
+  }
+}
+
+impl<'a> QueryFragment<'a> for FieldFilter<'a> {
+  fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+    let parameter = parameter_name(self.field);
+
+    query
+      .condition(format!("{} = ${parameter}", self.field))
+      .bind_value(&parameter, self.value)
+  }
+}
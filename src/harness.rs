@@ -0,0 +1,80 @@
+use serde::de::DeserializeOwned;
+use surrealdb::engine::local::Db;
+use surrealdb::engine::local::Mem;
+use surrealdb::Response;
+use surrealdb::Surreal;
+
+use crate::bindings::Bindings;
+use crate::model::SchemaRegistry;
+use crate::querybuilder::QueryBuilder;
+use crate::runner::QueryRunner;
+
+/// An in-memory SurrealDB database, to run the built queries on a real parser
+/// and engine in the tests rather than only comparing their text.
+///
+/// The database is empty and lives as long as the value, the tables of the
+/// models are defined with [`TestDatabase::define()`]. It needs a tokio runtime,
+/// like the one of `#[tokio::test]`.
+///
+/// ```rs
+/// #[tokio::test]
+/// async fn test_find_account() {
+///   let db = TestDatabase::new().await.unwrap();
+///   db.define(&SchemaRegistry::new().register(&account)).await.unwrap();
+///
+///   let accounts: Vec<Account> = QueryBuilder::new()
+///     .select("*")
+///     .from(account)
+///     .fetch_all(&db)
+///     .await
+///     .unwrap();
+///
+///   assert!(accounts.is_empty());
+/// }
+/// ```
+pub struct TestDatabase {
+  db: Surreal<Db>,
+}
+
+impl TestDatabase {
+  /// Start the database, in the `test` namespace and database.
+  pub async fn new() -> surrealdb::Result<Self> {
+    let db = Surreal::new::<Mem>(()).await?;
+    db.use_ns("test").use_db("test").await?;
+
+    Ok(Self { db })
+  }
+
+  /// Run the DEFINE statements of every model of the `registry`, see
+  /// [`SchemaModel::define_statements()`](crate::model::SchemaModel::define_statements).
+  pub async fn define(&self, registry: &SchemaRegistry<'_>) -> surrealdb::Result<()> {
+    let statements = registry.define_statements();
+
+    if !statements.is_empty() {
+      self.db.query(statements.join(";\n")).await?.check()?;
+    }
+
+    Ok(())
+  }
+
+  /// Run the query and return the response of every statement, the statements
+  /// that failed are returned as errors.
+  pub async fn run(&self, query: QueryBuilder<'_>) -> surrealdb::Result<Response> {
+    query.execute(&self.db).await?.check()
+  }
+
+  /// Return the client connected to the database.
+  pub fn client(&self) -> &Surreal<Db> {
+    &self.db
+  }
+}
+
+impl QueryRunner for TestDatabase {
+  type Error = surrealdb::Error;
+
+  async fn execute<T: DeserializeOwned>(
+    &self, query: &str, bindings: &Bindings,
+  ) -> Result<Vec<T>, Self::Error> {
+    self.db.execute(query, bindings).await
+  }
+}
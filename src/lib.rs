@@ -9,10 +9,74 @@ pub use surreal_simple_querybuilder_proc_macro::model;
 /// Contains a trait for simplifying the building of relationships between nodes
 pub mod node_builder;
 
+/// Contains the functions escaping identifiers and record ids.
+pub mod escape;
+
+/// Contains the trait writing Rust values as SurrealQL literals.
+pub mod literal;
+
+mod clauses;
+mod trace;
+mod validation;
+
 /// Contains the query builder for simplifying the building of Surreal QL queries.
 /// Particularely useful when composing variables and conditional queries
 pub mod querybuilder;
 
+/// Contains the operations replaying a query builder, to fuzz it or to store it.
+pub mod ops;
+
+/// Contains the reusable fragments of queries, applied to a query builder in a
+/// single call.
+pub mod fragment;
+
+/// Contains the versions of SurrealDB the queries can target.
+pub mod dialect;
+
+/// Contains the errors of the validated builds of the queries.
+pub mod build_error;
+
+/// Contains the values bound to the parameters of a query.
+pub mod bindings;
+
+/// Contains the helpers for the keyset pagination of the queries.
+pub mod cursor;
+
+/// Contains the trait building the usual CRUD queries of the models.
+pub mod crud;
+
+/// Contains the builder of the full-text search queries.
+pub mod search;
+
+/// Contains the builder of the aggregation queries.
+pub mod aggregate;
+
+/// Contains the trait to plug a database client into the query builder.
+pub mod runner;
+
+/// Contains the execution of the queries through the official client.
+#[cfg(feature = "client")]
+pub mod client;
+
+/// Contains an in-memory database to run the built queries on in tests.
+#[cfg(feature = "test-harness")]
+pub mod harness;
+
+/// Contains the detection of the differences between the models and the schema
+/// of the database.
+pub mod drift;
+
+/// Contains helpers to compare the built queries in tests without depending on
+/// their exact spacing.
+pub mod testing;
+
+/// Contains the generation of models from existing SurrealQL schemas.
+pub mod codegen;
+
+/// Contains the generation of the OpenAPI component schemas of the models.
+#[cfg(feature = "openapi")]
+pub mod openapi;
+
 /// Contains the `Foreign<T>` type used to represent fields that may or may not be
 /// loaded.
 // pub mod foreign;
@@ -0,0 +1,566 @@
+use serde::ser;
+use serde::Serialize;
+
+use crate::escape::escape_ident;
+use crate::escape::escape_str;
+use crate::model::SqlSerializeError;
+use crate::model::SqlSerializeResult;
+
+/// Implemented by the values that can be written as a SurrealQL literal, for
+/// example inside the array or object of a complex record id.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// assert_eq!("'o\\'brien'", "o'brien".to_sql_literal());
+/// assert_eq!("[2024, 'us']", vec![&2024 as &dyn SqlLiteral, &"us"].to_sql_literal());
+/// assert_eq!("NONE", None::<u32>.to_sql_literal());
+/// ```
+pub trait SqlLiteral {
+  fn to_sql_literal(&self) -> String;
+}
+
+impl SqlLiteral for str {
+  fn to_sql_literal(&self) -> String {
+    escape_str(self)
+  }
+}
+
+impl SqlLiteral for String {
+  fn to_sql_literal(&self) -> String {
+    escape_str(self)
+  }
+}
+
+impl SqlLiteral for bool {
+  fn to_sql_literal(&self) -> String {
+    self.to_string()
+  }
+}
+
+macro_rules! impl_number_literal {
+  ($($number:ty),*) => {
+    $(
+      impl SqlLiteral for $number {
+        fn to_sql_literal(&self) -> String {
+          self.to_string()
+        }
+      }
+    )*
+  };
+}
+
+impl_number_literal!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl SqlLiteral for f32 {
+  fn to_sql_literal(&self) -> String {
+    f64::from(*self).to_sql_literal()
+  }
+}
+
+/// The NaN and infinite values have no SurrealQL literal, they are written as
+/// `NaN` and `inf` which SurrealDB rejects. Unlike this trait [literal()] returns
+/// an error for them.
+impl SqlLiteral for f64 {
+  fn to_sql_literal(&self) -> String {
+    // the debug representation keeps the decimal part of round numbers, so they
+    // are not read back as integers.
+    format!("{self:?}")
+  }
+}
+
+impl<T: SqlLiteral + ?Sized> SqlLiteral for &T {
+  fn to_sql_literal(&self) -> String {
+    (**self).to_sql_literal()
+  }
+}
+
+impl<T: SqlLiteral> SqlLiteral for Option<T> {
+  fn to_sql_literal(&self) -> String {
+    match self {
+      Some(value) => value.to_sql_literal(),
+      None => "NONE".to_owned(),
+    }
+  }
+}
+
+impl<T: SqlLiteral> SqlLiteral for [T] {
+  fn to_sql_literal(&self) -> String {
+    array_literal(self.iter().map(SqlLiteral::to_sql_literal))
+  }
+}
+
+impl<T: SqlLiteral> SqlLiteral for Vec<T> {
+  fn to_sql_literal(&self) -> String {
+    self.as_slice().to_sql_literal()
+  }
+}
+
+/// Join the literals into an array literal like `[2024, 'us']`.
+pub(crate) fn array_literal(literals: impl Iterator<Item = String>) -> String {
+  format!("[{}]", literals.collect::<Vec<_>>().join(", "))
+}
+
+/// Join the fields into an object literal like `{ project: 'x', seq: 3 }`, the
+/// keys are escaped if needed.
+pub(crate) fn object_literal(fields: &[(&str, &dyn SqlLiteral)]) -> String {
+  let fields: Vec<String> = fields
+    .iter()
+    .map(|(key, value)| format!("{}: {}", escape_ident(key), value.to_sql_literal()))
+    .collect();
+
+  format!("{{ {} }}", fields.join(", "))
+}
+
+/// The name the [Datetime] newtype is serialized with, it allows the
+/// [LiteralSerializer] to recognize it among the other strings.
+const DATETIME_NAME: &str = "$surreal_simple_querybuilder::Datetime";
+
+/// A datetime written as a `d'...'` literal by [literal()], and as a plain
+/// string by any other serializer, for example when it is bound to a query.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let created_at = Datetime::new("2024-01-01T00:00:00Z");
+///
+/// assert_eq!("d'2024-01-01T00:00:00Z'", literal(&created_at).unwrap());
+/// assert_eq!("\"2024-01-01T00:00:00Z\"", serde_json::to_string(&created_at).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Datetime(String);
+
+impl Datetime {
+  /// Create a datetime from its ISO 8601 representation.
+  pub fn new(datetime: impl Into<String>) -> Self {
+    Self(datetime.into())
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+impl Serialize for Datetime {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: ser::Serializer,
+  {
+    serializer.serialize_newtype_struct(DATETIME_NAME, &self.0)
+  }
+}
+
+impl SqlLiteral for Datetime {
+  fn to_sql_literal(&self) -> String {
+    format!("d{}", escape_str(&self.0))
+  }
+}
+
+/// Render any serializable value as a SurrealQL literal: strings are quoted and
+/// escaped, sequences become arrays, maps and structs become objects and the
+/// [Datetime] values become datetime literals.
+///
+/// The unset options are written as `NONE`, the value SurrealDB stores in the
+/// `option<T>` fields, while the unit values are written as `NULL`. The NaN and
+/// infinite numbers have no literal and return an error.
+///
+/// It is meant for the places where a value cannot be bound to a parameter,
+/// like the default values of a DEFINE statement. Prefer
+/// [QueryBuilder::bind()](crate::querybuilder::QueryBuilder::bind) anywhere
+/// else.
+///
+/// # Example
+/// ```
+/// use serde::Serialize;
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// #[derive(Serialize)]
+/// struct Settings {
+///   theme: &'static str,
+///   sizes: Vec<u32>,
+///   owner: Option<String>,
+/// }
+///
+/// let settings = Settings {
+///   theme: "d'ark",
+///   sizes: vec![12, 16],
+///   owner: None,
+/// };
+///
+/// assert_eq!(
+///   "{ theme: 'd\\'ark', sizes: [12, 16], owner: NONE }",
+///   literal(&settings).unwrap()
+/// );
+/// ```
+pub fn literal<T: Serialize + ?Sized>(value: &T) -> SqlSerializeResult<String> {
+  let mut serializer = LiteralSerializer {
+    output: String::new(),
+  };
+  value.serialize(&mut serializer)?;
+  Ok(serializer.output)
+}
+
+/// The serializer behind [literal()], it writes the values it is given as
+/// SurrealQL literals.
+pub struct LiteralSerializer {
+  output: String,
+}
+
+impl LiteralSerializer {
+  fn compound(&mut self, opening: &str, closing: &'static str) -> LiteralCompound<'_> {
+    self.output += opening;
+
+    LiteralCompound {
+      serializer: self,
+      first: true,
+      closing,
+    }
+  }
+}
+
+/// Writes the elements of an array or the fields of an object, separated by
+/// commas, then the `closing` delimiters once it ends.
+pub struct LiteralCompound<'a> {
+  serializer: &'a mut LiteralSerializer,
+  first: bool,
+  closing: &'static str,
+}
+
+impl<'a> LiteralCompound<'a> {
+  fn separator(&mut self) {
+    if !self.first {
+      self.serializer.output += ", ";
+    }
+
+    self.first = false;
+  }
+
+  fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> SqlSerializeResult<()> {
+    self.separator();
+    value.serialize(&mut *self.serializer)
+  }
+
+  fn field<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> SqlSerializeResult<()> {
+    self.separator();
+    self.serializer.output += &escape_ident(key);
+    self.serializer.output += ": ";
+    value.serialize(&mut *self.serializer)
+  }
+
+  fn close(self) -> SqlSerializeResult<()> {
+    self.serializer.output += self.closing;
+    Ok(())
+  }
+}
+
+impl<'a> ser::Serializer for &'a mut LiteralSerializer {
+  type Ok = ();
+
+  type Error = SqlSerializeError;
+
+  type SerializeSeq = LiteralCompound<'a>;
+  type SerializeTuple = LiteralCompound<'a>;
+  type SerializeTupleStruct = LiteralCompound<'a>;
+  type SerializeTupleVariant = LiteralCompound<'a>;
+  type SerializeMap = LiteralCompound<'a>;
+  type SerializeStruct = LiteralCompound<'a>;
+  type SerializeStructVariant = LiteralCompound<'a>;
+
+  fn serialize_bool(self, v: bool) -> SqlSerializeResult<()> {
+    self.output += &v.to_sql_literal();
+    Ok(())
+  }
+
+  fn serialize_i8(self, v: i8) -> SqlSerializeResult<()> {
+    self.serialize_i64(i64::from(v))
+  }
+
+  fn serialize_i16(self, v: i16) -> SqlSerializeResult<()> {
+    self.serialize_i64(i64::from(v))
+  }
+
+  fn serialize_i32(self, v: i32) -> SqlSerializeResult<()> {
+    self.serialize_i64(i64::from(v))
+  }
+
+  fn serialize_i64(self, v: i64) -> SqlSerializeResult<()> {
+    self.output += &v.to_sql_literal();
+    Ok(())
+  }
+
+  fn serialize_u8(self, v: u8) -> SqlSerializeResult<()> {
+    self.serialize_u64(u64::from(v))
+  }
+
+  fn serialize_u16(self, v: u16) -> SqlSerializeResult<()> {
+    self.serialize_u64(u64::from(v))
+  }
+
+  fn serialize_u32(self, v: u32) -> SqlSerializeResult<()> {
+    self.serialize_u64(u64::from(v))
+  }
+
+  fn serialize_u64(self, v: u64) -> SqlSerializeResult<()> {
+    self.output += &v.to_sql_literal();
+    Ok(())
+  }
+
+  fn serialize_f32(self, v: f32) -> SqlSerializeResult<()> {
+    self.serialize_f64(f64::from(v))
+  }
+
+  fn serialize_f64(self, v: f64) -> SqlSerializeResult<()> {
+    if !v.is_finite() {
+      return Err(SqlSerializeError::Message(format!(
+        "{v} cannot be written as a SurrealQL literal"
+      )));
+    }
+
+    self.output += &v.to_sql_literal();
+    Ok(())
+  }
+
+  fn serialize_char(self, v: char) -> SqlSerializeResult<()> {
+    self.serialize_str(&v.to_string())
+  }
+
+  fn serialize_str(self, v: &str) -> SqlSerializeResult<()> {
+    self.output += &escape_str(v);
+    Ok(())
+  }
+
+  fn serialize_bytes(self, v: &[u8]) -> SqlSerializeResult<()> {
+    use serde::ser::SerializeSeq;
+    let mut seq = self.serialize_seq(Some(v.len()))?;
+    for byte in v {
+      seq.serialize_element(byte)?;
+    }
+    seq.end()
+  }
+
+  fn serialize_none(self) -> SqlSerializeResult<()> {
+    self.output += "NONE";
+    Ok(())
+  }
+
+  fn serialize_some<T>(self, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> SqlSerializeResult<()> {
+    self.output += "NULL";
+    Ok(())
+  }
+
+  fn serialize_unit_struct(self, _name: &'static str) -> SqlSerializeResult<()> {
+    self.serialize_unit()
+  }
+
+  fn serialize_unit_variant(
+    self, _name: &'static str, _variant_index: u32, variant: &'static str,
+  ) -> SqlSerializeResult<()> {
+    self.serialize_str(variant)
+  }
+
+  fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    if name == DATETIME_NAME {
+      self.output += "d";
+    }
+
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T>(
+    self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T,
+  ) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    let mut object = self.compound("{ ", " }");
+    object.field(variant, value)?;
+    object.close()
+  }
+
+  fn serialize_seq(self, _len: Option<usize>) -> SqlSerializeResult<Self::SerializeSeq> {
+    Ok(self.compound("[", "]"))
+  }
+
+  fn serialize_tuple(self, len: usize) -> SqlSerializeResult<Self::SerializeTuple> {
+    self.serialize_seq(Some(len))
+  }
+
+  fn serialize_tuple_struct(
+    self, _name: &'static str, len: usize,
+  ) -> SqlSerializeResult<Self::SerializeTupleStruct> {
+    self.serialize_seq(Some(len))
+  }
+
+  fn serialize_tuple_variant(
+    self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize,
+  ) -> SqlSerializeResult<Self::SerializeTupleVariant> {
+    self.output += "{ ";
+    self.output += &escape_ident(variant);
+    Ok(self.compound(": [", "] }"))
+  }
+
+  fn serialize_map(self, _len: Option<usize>) -> SqlSerializeResult<Self::SerializeMap> {
+    Ok(self.compound("{ ", " }"))
+  }
+
+  fn serialize_struct(
+    self, _name: &'static str, len: usize,
+  ) -> SqlSerializeResult<Self::SerializeStruct> {
+    self.serialize_map(Some(len))
+  }
+
+  fn serialize_struct_variant(
+    self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize,
+  ) -> SqlSerializeResult<Self::SerializeStructVariant> {
+    self.output += "{ ";
+    self.output += &escape_ident(variant);
+    Ok(self.compound(": { ", " } }"))
+  }
+}
+
+impl<'a> ser::SerializeSeq for LiteralCompound<'a> {
+  type Ok = ();
+  type Error = SqlSerializeError;
+
+  fn serialize_element<T>(&mut self, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.element(value)
+  }
+
+  fn end(self) -> SqlSerializeResult<()> {
+    self.close()
+  }
+}
+
+impl<'a> ser::SerializeTuple for LiteralCompound<'a> {
+  type Ok = ();
+  type Error = SqlSerializeError;
+
+  fn serialize_element<T>(&mut self, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.element(value)
+  }
+
+  fn end(self) -> SqlSerializeResult<()> {
+    self.close()
+  }
+}
+
+impl<'a> ser::SerializeTupleStruct for LiteralCompound<'a> {
+  type Ok = ();
+  type Error = SqlSerializeError;
+
+  fn serialize_field<T>(&mut self, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.element(value)
+  }
+
+  fn end(self) -> SqlSerializeResult<()> {
+    self.close()
+  }
+}
+
+impl<'a> ser::SerializeTupleVariant for LiteralCompound<'a> {
+  type Ok = ();
+  type Error = SqlSerializeError;
+
+  fn serialize_field<T>(&mut self, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.element(value)
+  }
+
+  fn end(self) -> SqlSerializeResult<()> {
+    self.close()
+  }
+}
+
+// The keys of an object literal must be identifiers, so the map keys are
+// turned into strings before being escaped.
+impl<'a> ser::SerializeMap for LiteralCompound<'a> {
+  type Ok = ();
+  type Error = SqlSerializeError;
+
+  fn serialize_key<T>(&mut self, key: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    let key = match serde_json::to_value(key) {
+      Ok(serde_json::Value::String(key)) => key,
+      Ok(number @ serde_json::Value::Number(..)) => number.to_string(),
+      _ => {
+        return Err(SqlSerializeError::Message(
+          "the keys of an object literal must be strings or numbers".to_owned(),
+        ))
+      }
+    };
+
+    self.separator();
+    self.serializer.output += &escape_ident(&key);
+    self.serializer.output += ": ";
+
+    Ok(())
+  }
+
+  fn serialize_value<T>(&mut self, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    value.serialize(&mut *self.serializer)
+  }
+
+  fn end(self) -> SqlSerializeResult<()> {
+    self.close()
+  }
+}
+
+impl<'a> ser::SerializeStruct for LiteralCompound<'a> {
+  type Ok = ();
+  type Error = SqlSerializeError;
+
+  fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.field(key, value)
+  }
+
+  fn end(self) -> SqlSerializeResult<()> {
+    self.close()
+  }
+}
+
+impl<'a> ser::SerializeStructVariant for LiteralCompound<'a> {
+  type Ok = ();
+  type Error = SqlSerializeError;
+
+  fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> SqlSerializeResult<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.field(key, value)
+  }
+
+  fn end(self) -> SqlSerializeResult<()> {
+    self.close()
+  }
+}
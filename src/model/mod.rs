@@ -1,10 +1,15 @@
 mod origin_holder;
 mod schema_field;
+mod schema_model;
+mod schema_registry;
 mod serialize_error;
 mod serializer;
 
 pub use origin_holder::OriginHolder;
 pub use schema_field::SchemaField;
 pub use schema_field::SchemaFieldType;
+pub use schema_model::SchemaIndex;
+pub use schema_model::SchemaModel;
+pub use schema_registry::SchemaRegistry;
 pub use serialize_error::*;
 pub use serializer::*;
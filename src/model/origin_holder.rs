@@ -1,6 +1,14 @@
 use std::fmt::Display;
 
+use crate::escape::escape_field;
+
+/// Holds the segments of the path that leads to a nested field, for example
+/// `["->", "manage->Project", "->", "has->Release"]`.
 ///
+/// The segments are `'static` strings stored in an array whose size is computed
+/// at compile time by the accessors the [`model!()`](crate::model) macro
+/// generates, so traversing deeply nested fields never allocates. The path is
+/// only written out when the field is displayed.
 #[derive(Clone)]
 pub struct OriginHolder<const N: usize> {
   pub segments: [&'static str; N],
@@ -9,7 +17,12 @@ pub struct OriginHolder<const N: usize> {
 impl<const N: usize> Display for OriginHolder<N> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     for segment in self.segments {
-      write!(f, "{segment}")?;
+      let is_path = segment.is_empty() || segment.contains(['.', '-', '<', '>']);
+
+      match is_path {
+        true => write!(f, "{segment}")?,
+        false => write!(f, "{}", escape_field(segment))?,
+      };
     }
 
     Ok(())
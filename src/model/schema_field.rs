@@ -3,6 +3,7 @@ use std::fmt::Display;
 
 use serde::Serialize;
 
+use crate::escape::escape_field;
 use crate::model::OriginHolder;
 use crate::node_builder::ToNodeBuilder;
 
@@ -87,6 +88,17 @@ impl<const N: usize> SchemaField<N> {
   }
 }
 
+impl<const N: usize> SchemaField<N> {
+  /// Return the identifier as it is written in the queries, the properties
+  /// named after a reserved word or holding unicode characters are escaped.
+  fn escaped_identifier(&self) -> Cow<'static, str> {
+    match self.field_type {
+      SchemaFieldType::Property => escape_field(self.identifier),
+      _ => Cow::Borrowed(self.identifier),
+    }
+  }
+}
+
 impl<const N: usize> Display for SchemaField<N> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match &self.origin_holder {
@@ -99,7 +111,7 @@ impl<const N: usize> Display for SchemaField<N> {
           SchemaFieldType::ForeignRelation => write!(f, "<-")?,
         };
 
-        write!(f, "{}", self.identifier)
+        write!(f, "{}", self.escaped_identifier())
       }
       None => {
         // prefix depending on the field type
@@ -109,7 +121,7 @@ impl<const N: usize> Display for SchemaField<N> {
           SchemaFieldType::ForeignRelation => write!(f, "<-")?,
         };
 
-        write!(f, "{}", self.identifier)
+        write!(f, "{}", self.escaped_identifier())
       }
     }
   }
@@ -117,22 +129,35 @@ impl<const N: usize> Display for SchemaField<N> {
 
 impl<const N: usize> ToNodeBuilder for SchemaField<N> {
   fn equals_parameterized(&self) -> String {
-    // special case for the schema field as it may include dots, we replace them
-    // by underscores.
-    format!(
-      "{self} = ${}",
-      self
-        .to_string()
-        .replace(".", "_")
-        .replace("->", "_")
-        .replace("<-", "_")
-    )
+    // special case for the schema field as it may include dots and arrows, we
+    // replace them by underscores. The parameter name is derived from the path
+    // in a single pass rather than through chained `replace` calls that would
+    // each allocate a new string. The brackets of the escaped names are dropped.
+    let path = self.to_string();
+    let mut output = String::with_capacity(path.len() * 2 + 4);
+    output.push_str(&path);
+    output.push_str(" = $");
+
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+      match (c, chars.peek()) {
+        ('.', _) => output.push('_'),
+        ('⟨' | '⟩', _) => {}
+        ('-', Some('>')) | ('<', Some('-')) => {
+          chars.next();
+          output.push('_');
+        }
+        (c, _) => output.push(c),
+      };
+    }
+
+    output
   }
 }
 
 impl<const N: usize> Into<Cow<'static, str>> for SchemaField<N> {
   fn into(self) -> Cow<'static, str> {
-    Cow::from(self.identifier)
+    self.escaped_identifier()
   }
 }
 
@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+use crate::escape::escape_field;
+
+/// Implemented by the models the [`model!()`](crate::model) macro generates, so
+/// their table and fields can be reached from generic code. The [Display]
+/// output of the model is the table escaped for the queries, see
+/// [escape_field](crate::escape::escape_field).
+pub trait SchemaModel: Display {
+  /// Returns the names of the fields stored on the node itself, the relations
+  /// excluded.
+  fn field_names(&self) -> &'static [&'static str];
+
+  /// Returns the name of the table unescaped, to compare it with the table of
+  /// the record ids or to bind it to a parameter.
+  fn table_name(&self) -> &'static str;
+
+  /// Returns the fields that declare a SurrealQL type, like `handle: string`,
+  /// along with their type.
+  fn field_types(&self) -> &'static [(&'static str, &'static str)] {
+    &[]
+  }
+
+  /// Returns the indexes declared on the table, like
+  /// `@unique account_handle(handle)`.
+  fn indexes(&self) -> &'static [SchemaIndex] {
+    &[]
+  }
+
+  /// Returns the DEFINE statements of the table, of its typed fields and of its
+  /// indexes. The fields without a type are left out as they are not defined
+  /// in a schemaless table.
+  ///
+  /// # Example
+  /// ```
+  /// #![allow(incomplete_features)]
+  /// #![feature(generic_const_exprs)]
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// model!(Account {
+  ///   id,
+  ///   pub handle: string,
+  ///   pub nickname,
+  ///   pub friends: array<record<Account>>,
+  ///
+  ///   @unique account_handle(handle),
+  /// });
+  ///
+  /// assert_eq!(
+  ///   vec![
+  ///     "DEFINE TABLE Account",
+  ///     "DEFINE FIELD handle ON TABLE Account TYPE string",
+  ///     "DEFINE FIELD friends ON TABLE Account TYPE array<record<Account>>",
+  ///     "DEFINE INDEX account_handle ON TABLE Account FIELDS handle UNIQUE",
+  ///   ],
+  ///   SchemaModel::define_statements(&schema::model)
+  /// );
+  /// ```
+  fn define_statements(&self) -> Vec<String> {
+    let table = self.to_string();
+    let mut statements = vec![format!("DEFINE TABLE {table}")];
+
+    for (field, sql_type) in self.field_types() {
+      statements.push(format!(
+        "DEFINE FIELD {} ON TABLE {table} TYPE {sql_type}",
+        escape_field(field)
+      ));
+    }
+
+    for index in self.indexes() {
+      let fields: Vec<_> = index
+        .fields
+        .iter()
+        .map(|field| escape_field(field))
+        .collect();
+      let unique = if index.is_unique { " UNIQUE" } else { "" };
+
+      statements.push(format!(
+        "DEFINE INDEX {} ON TABLE {table} FIELDS {}{unique}",
+        escape_field(index.name),
+        fields.join(", ")
+      ));
+    }
+
+    statements
+  }
+}
+
+/// An index of a table declared in a [`model!()`](crate::model), with
+/// `@index name(fields)` or `@unique name(fields)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchemaIndex {
+  pub name: &'static str,
+  pub fields: &'static [&'static str],
+  pub is_unique: bool,
+}
@@ -0,0 +1,70 @@
+use super::SchemaModel;
+
+/// A list of models, to run the same operation over every table of the schema
+/// like defining them or looking for their drift, see
+/// [detect_registry_drift](crate::drift::detect_registry_drift).
+///
+/// # Example
+/// ```
+/// #![allow(incomplete_features)]
+/// #![feature(generic_const_exprs)]
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// mod account {
+///   use surreal_simple_querybuilder::prelude::*;
+///
+///   model!(Account { id, pub handle: string });
+/// }
+///
+/// mod project {
+///   use surreal_simple_querybuilder::prelude::*;
+///
+///   model!(Project { id, pub name: string });
+/// }
+///
+/// let registry = SchemaRegistry::new()
+///   .register(&account::schema::model)
+///   .register(&project::schema::model);
+///
+/// assert_eq!(
+///   vec![
+///     "DEFINE TABLE Account",
+///     "DEFINE FIELD handle ON TABLE Account TYPE string",
+///     "DEFINE TABLE Project",
+///     "DEFINE FIELD name ON TABLE Project TYPE string",
+///   ],
+///   registry.define_statements()
+/// );
+/// ```
+#[derive(Default, Clone)]
+pub struct SchemaRegistry<'a> {
+  models: Vec<&'a dyn SchemaModel>,
+}
+
+impl<'a> SchemaRegistry<'a> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Add the model to the registry, the models are kept in the order they are
+  /// registered in.
+  pub fn register(mut self, model: &'a dyn SchemaModel) -> Self {
+    self.models.push(model);
+
+    self
+  }
+
+  pub fn models(&self) -> &[&'a dyn SchemaModel] {
+    &self.models
+  }
+
+  /// Returns the DEFINE statements of every registered model, see
+  /// [`SchemaModel::define_statements()`].
+  pub fn define_statements(&self) -> Vec<String> {
+    self
+      .models
+      .iter()
+      .flat_map(|model| model.define_statements())
+      .collect()
+  }
+}
@@ -1,3 +1,4 @@
+use crate::escape::escape_field;
 use crate::model::SqlSerializeError;
 use crate::model::SqlSerializeResult;
 use serde::{ser, Serialize};
@@ -304,7 +305,7 @@ impl<'a> ser::SerializeStruct for &'a mut SqlFieldSerializer {
       self.output += " , ";
     }
 
-    self.output += key;
+    self.output += &escape_field(key);
     self.output += " = $";
     self.output += key;
 
@@ -330,7 +331,7 @@ impl<'a> ser::SerializeStructVariant for &'a mut SqlFieldSerializer {
       self.output += " , ";
     }
 
-    self.output += key;
+    self.output += &escape_field(key);
     self.output += " = $";
     self.output += key;
 
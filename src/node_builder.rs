@@ -1,11 +1,21 @@
 use std::fmt::Display;
 
+use crate::escape::escape_ident;
+use crate::escape::escape_record_id;
+use crate::escape::escape_str;
+use crate::foreign_key::RecordId;
+use crate::literal::array_literal;
+use crate::literal::object_literal;
+use crate::literal::SqlLiteral;
+
 pub trait ToNodeBuilder<T: Display = Self>: Display {
   fn quoted(&self) -> String {
     format!("\"{self}\"")
   }
 
-  /// Draws the start of a relation `->node`
+  /// Draws the start of a relation `->node`. Any displayable value is accepted,
+  /// like the relation fields generated by the `model!()` macro, in which case
+  /// their own arrows are kept.
   ///
   /// # Example
   /// ```
@@ -15,18 +25,168 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
   ///
   /// assert_eq!("user->project", s);
   /// ```
-  fn with(&self, relation_or_node: &str) -> String {
+  fn with<N: Display>(&self, relation_or_node: N) -> String {
     // write the arrow only if the first character is not a special character.
     // there are cases where the `node` string that was passed starts with
     // an arrow or a dot, in which case we do not want to push a new arrow
     // ourselves.
-    if !relation_or_node.starts_with("->") && !relation_or_node.starts_with(".") {
+    let relation_or_node = relation_or_node.to_string();
+    let relation_or_node = escape_record_id(&relation_or_node);
+
+    if !starts_with_arrow(&relation_or_node) && !relation_or_node.starts_with(".") {
       format!("{self}->{relation_or_node}")
     } else {
       format!("{self}{relation_or_node}")
     }
   }
 
+  /// Draws the start of an incoming relation `<-node`, the reverse of
+  /// [`ToNodeBuilder::with()`]
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "release".with_back("has").with_back("Project");
+  ///
+  /// assert_eq!("release<-has<-Project", s);
+  /// ```
+  fn with_back<N: Display>(&self, relation_or_node: N) -> String {
+    // same as `with`, the arrow is written only if the passed string does not
+    // already start with an arrow or a dot.
+    let relation_or_node = relation_or_node.to_string();
+    let relation_or_node = escape_record_id(&relation_or_node);
+
+    if !starts_with_arrow(&relation_or_node) && !relation_or_node.starts_with(".") {
+      format!("{self}<-{relation_or_node}")
+    } else {
+      format!("{self}{relation_or_node}")
+    }
+  }
+
+  /// Draws an outgoing hop `->edge`, to start a hop whose direction is then
+  /// followed by [`ToNodeBuilder::to()`]
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "user"
+  ///   .out("manage")
+  ///   .to("Project")
+  ///   .in_("has")
+  ///   .to("Release");
+  ///
+  /// assert_eq!("user->manage->Project<-has<-Release", s);
+  /// ```
+  fn out(&self, edge: &str) -> String {
+    format!("{self}->{edge}")
+  }
+
+  /// Draws an incoming hop `<-edge`, see [`ToNodeBuilder::out()`]
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "release".in_("has").to("Project");
+  ///
+  /// assert_eq!("release<-has<-Project", s);
+  /// ```
+  fn in_(&self, edge: &str) -> String {
+    format!("{self}<-{edge}")
+  }
+
+  /// Draws the node at the end of the current hop, using the same direction as
+  /// the last arrow of the current string. Defaults to `->` if there is no arrow
+  /// yet.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// assert_eq!("user->manage->Project", "user".out("manage").to("Project"));
+  /// assert_eq!("user<-manage<-Project", "user".in_("manage").to("Project"));
+  /// ```
+  fn to(&self, node: &str) -> String {
+    let current = self.to_string();
+
+    format!(
+      "{current}{}{}",
+      last_arrow(&current),
+      escape_record_id(node)
+    )
+  }
+
+  /// Draws `depth` outgoing hops through the `edge` to reach the ancestors of a
+  /// node in a hierarchy where the children are related to their parents, like
+  /// `child->child_of->parent`. A depth of zero draws nothing.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "Category:rust".ancestors("child_of", 2);
+  ///
+  /// assert_eq!("Category:rust->child_of->?->child_of->?", s);
+  /// ```
+  fn ancestors(&self, edge: &str, depth: usize) -> String {
+    format!("{self}{}", format!("->{edge}->?").repeat(depth))
+  }
+
+  /// Draws `depth` incoming hops through the `edge` to reach the descendants of
+  /// a node, the reverse of [`ToNodeBuilder::ancestors()`].
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "Category:lang".descendants("child_of", 2);
+  ///
+  /// assert_eq!("Category:lang<-child_of<-?<-child_of<-?", s);
+  /// ```
+  fn descendants(&self, edge: &str, depth: usize) -> String {
+    format!("{self}{}", format!("<-{edge}<-?").repeat(depth))
+  }
+
+  /// Walks up to `depth` outgoing hops through the `edge` using the recursive
+  /// syntax of SurrealDB 2.1 and later, see [`ToNodeBuilder::ancestors()`].
+  ///
+  /// The path is written for any [Dialect](crate::dialect::Dialect), it is only
+  /// rejected for 1.x when the query is built with
+  /// [`QueryBuilder::try_build_with()`](crate::querybuilder::QueryBuilder::try_build_with).
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "Category:rust".ancestors_recursive("child_of", 3);
+  ///
+  /// assert_eq!("Category:rust.{..3}->child_of->?", s);
+  /// ```
+  fn ancestors_recursive(&self, edge: &str, depth: usize) -> String {
+    format!("{self}.{{..{depth}}}->{edge}->?")
+  }
+
+  /// Walks up to `depth` incoming hops through the `edge` using the recursive
+  /// syntax of SurrealDB 2.1 and later, see [`ToNodeBuilder::descendants()`].
+  ///
+  /// The path is written for any [Dialect](crate::dialect::Dialect), it is only
+  /// rejected for 1.x when the query is built with
+  /// [`QueryBuilder::try_build_with()`](crate::querybuilder::QueryBuilder::try_build_with).
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "Category:lang".descendants_recursive("child_of", 3);
+  ///
+  /// assert_eq!("Category:lang.{..3}<-child_of<-?", s);
+  /// ```
+  fn descendants_recursive(&self, edge: &str, depth: usize) -> String {
+    format!("{self}.{{..{depth}}}<-{edge}<-?")
+  }
+
   /// Draws the end of a relation `<-node`
   ///
   /// # Example
@@ -38,7 +198,7 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
   /// assert_eq!("user<-project", s);
   /// ```
   fn from(&self, node: &str) -> String {
-    format!("{self}<-{node}")
+    format!("{self}<-{}", escape_record_id(node))
   }
 
   /// Take the current string and add in front of it the given label name as to
@@ -53,7 +213,7 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
   /// assert_eq!(label, "Account:John");
   /// ```
   fn as_named_label(&self, label_name: &str) -> String {
-    format!("{label_name}:{self}")
+    RecordId::new(label_name, self.to_string()).to_string()
   }
 
   /// # Example
@@ -69,6 +229,19 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
     format!("{self} = {value}")
   }
 
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "user".not_equals("John");
+  ///
+  /// // Note that it doesn't add quotes around strings
+  /// assert_eq!("user != John", s);
+  /// ```
+  fn not_equals(&self, value: &str) -> String {
+    format!("{self} != {value}")
+  }
+
   /// Take the current string and add `= $current_string` after it
   ///
   /// # Example
@@ -83,6 +256,25 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
     format!("{self} = ${self}")
   }
 
+  /// Take the current string and add `operator $placeholder` after it. The
+  /// leading `$` of the placeholder is optional.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "age".compared_parameterized(">=", "min_age");
+  /// assert_eq!("age >= $min_age", s);
+  ///
+  /// let s = "age".compared_parameterized("<", "$max_age");
+  /// assert_eq!("age < $max_age", s);
+  /// ```
+  fn compared_parameterized(&self, operator: &str, placeholder: &str) -> String {
+    let placeholder = placeholder.trim_start_matches('$');
+
+    format!("{self} {operator} ${placeholder}")
+  }
+
   /// Take the current string and add `> value` after it
   ///
   /// # Example
@@ -171,6 +363,72 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
     format!("{self} CONTAINSNONE {values}")
   }
 
+  /// Take the current string and add `IN [values]` after it. Every value is
+  /// written as a literal, so the strings are quoted and escaped while the
+  /// numbers and the [RecordId](crate::foreign_key::RecordId) are not.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "handle".in_list(&["john", "o'brien"]);
+  /// assert_eq!("handle IN ['john', 'o\\'brien']", s);
+  ///
+  /// let s = "age".in_list(&[18, 21]);
+  /// assert_eq!("age IN [18, 21]", s);
+  ///
+  /// let s = "author".in_list(&[RecordId::new("Account", "john")]);
+  /// assert_eq!("author IN [Account:john]", s);
+  /// ```
+  fn in_list<V: SqlLiteral>(&self, values: &[V]) -> String {
+    format!("{self} IN {}", values.to_sql_literal())
+  }
+
+  /// Take the current string and add `NOT IN [values]` after it. Every value is
+  /// written as a literal, like [`ToNodeBuilder::in_list()`] does.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "handle".not_in_list(&["john", "mark"]);
+  ///
+  /// assert_eq!("handle NOT IN ['john', 'mark']", s);
+  /// ```
+  fn not_in_list<V: SqlLiteral>(&self, values: &[V]) -> String {
+    format!("{self} NOT IN {}", values.to_sql_literal())
+  }
+
+  /// Take the current string and add `IN $param` after it. The leading `$` of
+  /// the parameter is optional.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "id".in_param("$ids");
+  ///
+  /// assert_eq!("id IN $ids", s);
+  /// ```
+  fn in_param(&self, param: &str) -> String {
+    self.compared_parameterized("IN", param)
+  }
+
+  /// Take the current string and add `NOT IN $param` after it. The leading `$`
+  /// of the parameter is optional.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "id".not_in_param("ids");
+  ///
+  /// assert_eq!("id NOT IN $ids", s);
+  /// ```
+  fn not_in_param(&self, param: &str) -> String {
+    self.compared_parameterized("NOT IN", param)
+  }
+
   /// Take the current string and add `as alias` after it
   ///
   /// # Example
@@ -185,6 +443,37 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
     format!("{self} AS {alias}")
   }
 
+  /// Take the current string and add `.*` after it in order to project every
+  /// field of the object it points to.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "author".all_fields();
+  ///
+  /// assert_eq!("author.*", s);
+  /// ```
+  fn all_fields(&self) -> String {
+    format!("{self}.*")
+  }
+
+  /// Take the current string and add `.*` after it `depth` times, so that the
+  /// nested objects it points to are projected as well. A `depth` of `0` leaves
+  /// the string intact.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "author".all_fields_nested(2);
+  ///
+  /// assert_eq!("author.*.*", s);
+  /// ```
+  fn all_fields_nested(&self, depth: usize) -> String {
+    format!("{self}{}", ".*".repeat(depth))
+  }
+
   /// Take the current string, extract the last segment if it is a nested property,
   /// then add parenthesis around it and add the supplied condition in them.
   ///
@@ -218,6 +507,22 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
     format!("{left}({right} WHERE {condition})")
   }
 
+  /// Take the current string and add the supplied condition in brackets after
+  /// it, which is how SurrealQL filters the nodes of a traversal inline.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let path = "->manage->Project";
+  /// let s = path.filtered("name CONTAINS 'api'");
+  ///
+  /// assert_eq!("->manage->Project[WHERE name CONTAINS 'api']", s);
+  /// ```
+  fn filtered(&self, condition: &str) -> String {
+    format!("{self}[WHERE {condition}]")
+  }
+
   /// write a comma at the end of the string and append `right` after it.
   ///
   /// # Example
@@ -249,6 +554,76 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
     format!("count({self})")
   }
 
+  /// write a `time::floor()` around the current string, rounding the datetime
+  /// down to the nearest multiple of the supplied `duration`.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let day = "created_at".time_floor("1d");
+  /// let query = QueryBuilder::new()
+  ///   .select(day.as_alias("day"))
+  ///   .also("count()")
+  ///   .from("Order")
+  ///   .group_by("day")
+  ///   .build();
+  ///
+  /// assert_eq!(
+  ///   "SELECT time::floor(created_at, 1d) AS day , count() FROM Order GROUP BY day",
+  ///   query
+  /// );
+  /// ```
+  fn time_floor(&self, duration: &str) -> String {
+    format!("time::floor({self}, {duration})")
+  }
+
+  /// write a `time::round()` around the current string, rounding the datetime
+  /// to the nearest multiple of the supplied `duration`.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "created_at".time_round("1h");
+  ///
+  /// assert_eq!("time::round(created_at, 1h)", s);
+  /// ```
+  fn time_round(&self, duration: &str) -> String {
+    format!("time::round({self}, {duration})")
+  }
+
+  /// write a `time::format()` around the current string, the `format` is
+  /// quoted automatically.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "created_at".time_format("%Y-%m-%d");
+  ///
+  /// assert_eq!("time::format(created_at, '%Y-%m-%d')", s);
+  /// ```
+  fn time_format(&self, format: &str) -> String {
+    format!("time::format({self}, {})", escape_str(format))
+  }
+
+  /// write a `time::group()` around the current string, grouping the datetime
+  /// by the supplied `group` (`year`, `month`, `day`, `hour`, ...). The group
+  /// is quoted automatically.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = "created_at".time_group("month");
+  ///
+  /// assert_eq!("time::group(created_at, 'month')", s);
+  /// ```
+  fn time_group(&self, group: &str) -> String {
+    format!("time::group({self}, {})", escape_str(group))
+  }
+
   /// Add the supplied `id` right after the current string in order to get the a
   /// new string in the following format `current:id`
   /// # Example
@@ -260,7 +635,91 @@ pub trait ToNodeBuilder<T: Display = Self>: Display {
   /// assert_eq!(query, "Account:John");
   /// ```
   fn with_id(&self, id: &str) -> String {
-    format!("{self}:{id}")
+    format!("{self}:{}", escape_ident(id))
+  }
+
+  /// Add the supplied array `id` right after the current string in order to get
+  /// a complex record id in the following format `current:[values]`. Every value
+  /// is written as a literal.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let event = "event".with_array_id(&[&2024, &"us"]);
+  ///
+  /// assert_eq!(event, "event:[2024, 'us']");
+  /// assert_eq!(
+  ///   "Account:John->attend->event:[2024, 'us']",
+  ///   "Account:John".with("attend").with(&event)
+  /// );
+  /// ```
+  fn with_array_id(&self, values: &[&dyn SqlLiteral]) -> String {
+    format!(
+      "{self}:{}",
+      array_literal(values.iter().map(|value| value.to_sql_literal()))
+    )
+  }
+
+  /// Add the supplied object `id` right after the current string in order to get
+  /// a complex record id in the following format `current:{ key: value }`. Every
+  /// value is written as a literal.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let task = "task".with_object_id(&[("project", &"x"), ("seq", &3)]);
+  ///
+  /// assert_eq!(task, "task:{ project: 'x', seq: 3 }");
+  /// ```
+  fn with_object_id(&self, fields: &[(&str, &dyn SqlLiteral)]) -> String {
+    format!("{self}:{}", object_literal(fields))
+  }
+
+  /// Add a random id generated by the database right after the current string in
+  /// order to get a new string in the following format `current:rand()`
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new().create("Account".with_rand_id()).build();
+  ///
+  /// assert_eq!(query, "CREATE Account:rand()");
+  /// ```
+  fn with_rand_id(&self) -> String {
+    format!("{self}:rand()")
+  }
+
+  /// Add a ULID generated by the database right after the current string in
+  /// order to get a new string in the following format `current:ulid()`
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = "Account".with_ulid_id();
+  ///
+  /// assert_eq!(query, "Account:ulid()");
+  /// ```
+  fn with_ulid_id(&self) -> String {
+    format!("{self}:ulid()")
+  }
+
+  /// Add a UUID generated by the database right after the current string in
+  /// order to get a new string in the following format `current:uuid()`
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = "Account".with_uuid_id();
+  ///
+  /// assert_eq!(query, "Account:uuid()");
+  /// ```
+  fn with_uuid_id(&self) -> String {
+    format!("{self}:uuid()")
   }
 }
 
@@ -286,7 +745,9 @@ impl<'a> ToNodeBuilder for &'a str {
 }
 
 pub trait NodeBuilder<T: Display = Self>: Display {
-  /// Draws the start of a relation `->node`
+  /// Draws the start of a relation `->node`. Any displayable value is accepted,
+  /// like the relation fields generated by the `model!()` macro, in which case
+  /// their own arrows are kept.
   ///
   /// # Example
   /// ```
@@ -296,11 +757,24 @@ pub trait NodeBuilder<T: Display = Self>: Display {
   ///
   /// assert_eq!("user->project", s);
   /// ```
-  fn with(&mut self, relation_or_node: &str) -> &mut String;
+  fn with<N: Display>(&mut self, relation_or_node: N) -> &mut String;
+
+  /// Draws the start of an incoming relation `<-node`
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let s = &mut "release".to_owned();
+  /// s.with_back("has").with_back("Project");
+  ///
+  /// assert_eq!("release<-has<-Project", *s);
+  /// ```
+  fn with_back<N: Display>(&mut self, relation_or_node: N) -> &mut String;
 
-  /// Allows you to pass a lambda that should mutate the current string when the
+  /// Allows you to pass a closure that should mutate the current string when the
   /// passed `condition` is `true`. If `condition` is `false` then the `action`
-  /// lambda is ignored and the string stays intact.
+  /// closure is ignored and the string stays intact.
   ///
   /// # Example
   /// ```
@@ -323,8 +797,17 @@ pub trait NodeBuilder<T: Display = Self>: Display {
   ///   .with("User");
   ///
   /// assert_eq!("User:John->LOVES->User->FRIEND->User", *modified);
+  ///
+  /// // demonstrate how the closure can capture variables
+  /// let friend = "Mark".as_named_label("User");
+  /// let mut label = "John".as_named_label("User");
+  /// let captured = &mut label.if_then(true, |s| s.with("FRIEND").with(&friend));
+  ///
+  /// assert_eq!("User:John->FRIEND->User:Mark", *captured);
   /// ```
-  fn if_then(&mut self, condition: bool, action: fn(&mut Self) -> &mut Self) -> &mut String;
+  fn if_then<F>(&mut self, condition: bool, action: F) -> &mut String
+  where
+    F: FnOnce(&mut Self) -> &mut Self;
 
   /// Take the current string add add `> value` after it
   ///
@@ -352,21 +835,40 @@ pub trait NodeBuilder<T: Display = Self>: Display {
 }
 
 impl NodeBuilder for String {
-  fn with(&mut self, node: &str) -> &mut String {
+  fn with<N: Display>(&mut self, node: N) -> &mut String {
+    let node = node.to_string();
+    let node = escape_record_id(&node);
+
     // push the arrow only if the first character is not a special character.
     // there are cases where the `node` string that was passed starts with
     // an arrow or a dot, in which case we do not want to push a new arrow
     // ourselves.
-    if !node.starts_with("->") && !node.starts_with(".") {
+    if !starts_with_arrow(&node) && !node.starts_with(".") {
       self.push_str("->");
     }
 
-    self.push_str(node);
+    self.push_str(&node);
 
     self
   }
 
-  fn if_then(&mut self, condition: bool, action: fn(&mut Self) -> &mut Self) -> &mut String {
+  fn with_back<N: Display>(&mut self, node: N) -> &mut String {
+    let node = node.to_string();
+    let node = escape_record_id(&node);
+
+    if !starts_with_arrow(&node) && !node.starts_with(".") {
+      self.push_str("<-");
+    }
+
+    self.push_str(&node);
+
+    self
+  }
+
+  fn if_then<F>(&mut self, condition: bool, action: F) -> &mut String
+  where
+    F: FnOnce(&mut Self) -> &mut Self,
+  {
     match condition {
       true => action(self),
       false => self,
@@ -389,3 +891,76 @@ impl NodeBuilder for String {
 }
 
 impl ToNodeBuilder for String {}
+
+/// Negate the supplied expression with a `!`. Anything but a plain identifier or
+/// parameter is wrapped in parenthesis so the negation applies to the whole
+/// expression and not only to its first operand.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// assert_eq!("!archived", not("archived"));
+/// assert_eq!("!$archived", not("$archived"));
+/// assert_eq!("!(age > 10 AND age < 20)", not("age > 10 AND age < 20"));
+/// assert_eq!("!(x-1)", not("x-1"));
+///
+/// // expressions that are already grouped are not wrapped a second time
+/// assert_eq!("!(age > 10)", not("(age > 10)"));
+/// ```
+pub fn not(expression: &str) -> String {
+  let expression = expression.trim();
+
+  if is_plain_operand(expression) || is_grouped(expression) {
+    format!("!{expression}")
+  } else {
+    format!("!({expression})")
+  }
+}
+
+/// Return whether the expression is a plain identifier or parameter, the only
+/// operands that can be negated without parenthesis.
+fn is_plain_operand(expression: &str) -> bool {
+  let name = expression.strip_prefix('$').unwrap_or(expression);
+
+  !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Return whether the whole expression is surrounded by a single pair of
+/// parenthesis, meaning `(a) AND (b)` is not grouped while `((a) AND (b))` is.
+fn is_grouped(expression: &str) -> bool {
+  if !expression.starts_with('(') || !expression.ends_with(')') {
+    return false;
+  }
+
+  let mut depth = 0;
+  for (index, c) in expression.char_indices() {
+    match c {
+      '(' => depth += 1,
+      ')' => depth -= 1,
+      _ => {}
+    };
+
+    if depth == 0 {
+      return index == expression.len() - 1;
+    }
+  }
+
+  false
+}
+
+/// Return the direction of the last arrow in the traversal, `->` if there is
+/// none.
+fn last_arrow(traversal: &str) -> &'static str {
+  match (traversal.rfind("->"), traversal.rfind("<-")) {
+    (Some(outgoing), Some(incoming)) if incoming > outgoing => "<-",
+    (None, Some(_)) => "<-",
+    _ => "->",
+  }
+}
+
+/// Return whether the node starts with an arrow in either direction, like the
+/// relation fields of the models do.
+fn starts_with_arrow(node: &str) -> bool {
+  node.starts_with("->") || node.starts_with("<-")
+}
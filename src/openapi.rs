@@ -0,0 +1,100 @@
+use schemars::gen::SchemaGenerator;
+use schemars::gen::SchemaSettings;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::ser::SerializeMap;
+use serde::Serialize;
+
+/// The component schemas of the models, to embed in the `components` object of
+/// an OpenAPI 3 document.
+///
+/// Every registered model is added along with the models it references, which
+/// are written as references to their own components. The foreign links are
+/// inlined as the union of their nullable key and their loaded value, see the
+/// `JsonSchema` implementation of [ForeignKey](crate::foreign_key::ForeignKey).
+///
+/// It serializes into a `{ "schemas": { ... } }` object:
+/// ```rs
+/// #[derive(JsonSchema)]
+/// struct Account {
+///   handle: String,
+/// }
+///
+/// #[derive(JsonSchema)]
+/// struct File {
+///   name: String,
+///   author: Foreign<Account>,
+/// }
+///
+/// let components = OpenApiComponents::new().register::<File>();
+///
+/// assert_eq!(
+///   serde_json::json!({
+///     "schemas": {
+///       "Account": {
+///         "type": "object",
+///         "required": ["handle"],
+///         "properties": { "handle": { "type": "string" } }
+///       },
+///       "File": {
+///         "type": "object",
+///         "required": ["author", "name"],
+///         "properties": {
+///           "author": {
+///             "anyOf": [
+///               { "type": "string", "nullable": true },
+///               { "$ref": "#/components/schemas/Account" }
+///             ]
+///           },
+///           "name": { "type": "string" }
+///         }
+///       }
+///     }
+///   }),
+///   serde_json::to_value(&components)?
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenApiComponents {
+  gen: SchemaGenerator,
+}
+
+impl OpenApiComponents {
+  pub fn new() -> Self {
+    Self {
+      gen: SchemaSettings::openapi3().into_generator(),
+    }
+  }
+
+  /// Add the schema of the model, and of the models it references, to the
+  /// components. The models that are not referenceable, like the strings or the
+  /// foreign links, have no component of their own.
+  pub fn register<T: JsonSchema>(mut self) -> Self {
+    self.gen.subschema_for::<T>();
+
+    self
+  }
+
+  /// Return the schemas of the components, by their names.
+  pub fn schemas(&self) -> &schemars::Map<String, Schema> {
+    self.gen.definitions()
+  }
+}
+
+impl Default for OpenApiComponents {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Serialize for OpenApiComponents {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry("schemas", self.schemas())?;
+
+    map.end()
+  }
+}
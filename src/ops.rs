@@ -0,0 +1,156 @@
+use std::borrow::Cow;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::querybuilder::QueryBuilder;
+
+/// A single call to a method of the [QueryBuilder], so a query can be described
+/// as plain data and replayed with [`QueryBuilder::from_ops()`]. It is meant
+/// for fuzzing and property testing the builder, where the operations are
+/// generated rather than written by hand.
+///
+/// The text of the operations is either borrowed with `Op<&str>` or owned with
+/// `Op<String>`. The operations serialize with Serde, so a list of them can be
+/// cached to disk, sent to another service or attached to a bug report and
+/// replayed later into the same query.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let ops = [Op::Select("*"), Op::From("Account"), Op::Limit(10)];
+/// let json = serde_json::to_string(&ops).unwrap();
+///
+/// assert_eq!(r#"[{"Select":"*"},{"From":"Account"},{"Limit":10}]"#, json);
+///
+/// let replayed: Vec<Op<String>> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(
+///   QueryBuilder::from_ops(&ops).build(),
+///   QueryBuilder::from_ops(&replayed).build()
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Op<S> {
+  Create(S),
+  Update(S),
+  Select(S),
+  Delete(S),
+  Relate(S),
+  Content(S),
+  From(S),
+  Also(S),
+  Filter(S),
+  And(S),
+  Or(S),
+  Set(S),
+  Fetch(S),
+  GroupBy(S),
+  OrderByAsc(S),
+  OrderByDesc(S),
+  Limit(u64),
+  StartAt(u64),
+  Raw(S),
+  Param(S, S),
+
+  /// Open a group with its first condition, like [`QueryBuilder::and_group()`]
+  /// does, or start the WHERE clause with it if there is none yet. The group is
+  /// closed by the matching [`Op::EndGroup`], by the first operation that does
+  /// not continue the condition, like [`Op::Limit`], or at the end of the
+  /// operations.
+  AndGroup(S),
+
+  /// Close the last group opened by an [`Op::AndGroup`], it does nothing if no
+  /// group is open.
+  EndGroup,
+}
+
+impl<'a> QueryBuilder<'a> {
+  /// Build a query by applying the operations in order. The same operations
+  /// always give the same query, and the groups are always balanced and closed
+  /// before the clauses following the WHERE clause no matter how the operations
+  /// are arranged.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::from_ops(&[
+  ///   Op::Select("*"),
+  ///   Op::From("user"),
+  ///   Op::Filter("name contains 'John'"),
+  ///   Op::AndGroup("name contains 'Doe'"),
+  ///   Op::Or("name contains 'Eod'"),
+  ///   Op::Limit(10),
+  /// ])
+  /// .build();
+  ///
+  /// assert_eq!(
+  ///   "SELECT * FROM user WHERE name contains 'John' AND ( name contains 'Doe' OR name contains 'Eod' ) LIMIT 10",
+  ///   query
+  /// );
+  /// ```
+  pub fn from_ops<S>(ops: &[Op<S>]) -> Self
+  where
+    S: Into<Cow<'a, str>> + Clone,
+  {
+    let mut open_groups = 0;
+
+    let query = ops
+      .iter()
+      .cloned()
+      .fold(QueryBuilder::new(), |mut query, op| {
+        let continues_condition = matches!(
+          op,
+          Op::And(_) | Op::Or(_) | Op::Raw(_) | Op::Param(..) | Op::AndGroup(_) | Op::EndGroup
+        );
+
+        if !continues_condition {
+          query = (0..open_groups).fold(query, |query, _| query.segment(")"));
+          open_groups = 0;
+        }
+
+        match op {
+          Op::Create(node) => query.create(node),
+          Op::Update(node) => query.update(node),
+          Op::Select(node) => query.select(node),
+          Op::Delete(node) => query.delete(node),
+          Op::Relate(node) => query.relate(node),
+          Op::Content(content) => query.content(content),
+          Op::From(node) => query.from(node),
+          Op::Also(node) => query.also(node),
+          Op::Filter(condition) => query.filter(condition),
+          Op::And(condition) => query.and(condition),
+          Op::Or(condition) => query.or(condition),
+          Op::Set(update) => query.set(update),
+          Op::Fetch(field) => query.fetch(field),
+          Op::GroupBy(field) => query.group_by(field),
+          Op::OrderByAsc(field) => query.order_by_asc(field),
+          Op::OrderByDesc(field) => query.order_by_desc(field),
+          Op::Limit(limit) => query.limit(limit),
+          Op::StartAt(offset) => query.start_at(offset),
+          Op::Raw(text) => query.raw(text),
+          Op::Param(key, value) => query.param(key, value),
+          Op::AndGroup(condition) => {
+            open_groups += 1;
+
+            let keyword = if query.has_condition() {
+              "AND"
+            } else {
+              "WHERE"
+            };
+
+            query.segment(keyword).segment("(").segment(condition)
+          }
+          Op::EndGroup if open_groups > 0 => {
+            open_groups -= 1;
+
+            query.segment(")")
+          }
+          Op::EndGroup => query,
+        }
+      });
+
+    (0..open_groups).fold(query, |query, _| query.segment(")"))
+  }
+}
@@ -1,5 +1,23 @@
+pub use crate::aggregate::*;
+pub use crate::bindings::*;
+pub use crate::build_error::*;
+pub use crate::crud::*;
+pub use crate::cursor::*;
+pub use crate::dialect::*;
+pub use crate::drift::*;
+pub use crate::escape::*;
 pub use crate::foreign_key::*;
+pub use crate::fragment::*;
+#[cfg(feature = "test-harness")]
+pub use crate::harness::*;
+pub use crate::literal::*;
 pub use crate::model;
 pub use crate::model::*;
 pub use crate::node_builder::*;
+#[cfg(feature = "openapi")]
+pub use crate::openapi::*;
+pub use crate::ops::*;
+pub use crate::query;
 pub use crate::querybuilder::*;
+pub use crate::runner::*;
+pub use crate::search::*;
@@ -1,14 +1,130 @@
-use std::{borrow::Cow, collections::HashMap};
-
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::ops::Range;
+use std::{borrow::Cow, collections::HashMap, fmt::Display, marker::PhantomData, sync::Mutex};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use serde::Serialize;
 
+use crate::bindings::Bindings;
+use crate::build_error::QueryBuilderError;
+use crate::build_error::QueryBuilderResult;
+use crate::cursor::Direction;
+use crate::dialect::Dialect;
+use crate::escape::escape_field;
+use crate::escape::escape_ident;
+use crate::escape::escape_record_id;
+use crate::escape::escape_str;
+use crate::model::SchemaModel;
+use crate::prelude::SqlSerializeError;
 use crate::prelude::SqlSerializeResult;
+use crate::prelude::ToNodeBuilder;
+use crate::runner::QueryRunner;
 
 type CowSegment<'a> = Cow<'a, str>;
+type BuildHook<'a> = Box<dyn Fn(&str, &Bindings) + 'a>;
+type SegmentHook<'a> = Box<dyn FnOnce(&mut Vec<CowSegment<'a>>) + 'a>;
 
 pub struct QueryBuilder<'a> {
   segments: Vec<CowSegment<'a>>,
-  parameters: HashMap<&'a str, &'a str>,
+  parameters: HashMap<CowSegment<'a>, CowSegment<'a>>,
+  bindings: Bindings,
+  build_hooks: Vec<BuildHook<'a>>,
+  segment_hooks: Vec<SegmentHook<'a>>,
+
+  /// The methods that inserted unchecked text in the query, reported by
+  /// [`BuildOptions::deny_raw_text`].
+  raw_text_methods: Vec<&'static str>,
+
+  /// Every change made to the parameters, bindings and existing segments,
+  /// undone in reverse order by [`QueryBuilder::rollback_to()`].
+  changes: Vec<Change<'a>>,
+}
+
+/// A parameter or binding set on a [QueryBuilder], with the value it replaced,
+/// or segments replaced before the end of the query.
+enum Change<'a> {
+  Parameter(CowSegment<'a>, Option<CowSegment<'a>>),
+  Binding(String, Option<serde_json::Value>),
+
+  /// The segments replaced from `index`, the number of segments inserted in
+  /// their place and the number of segments right after the change.
+  Segments {
+    index: usize,
+    replaced: Vec<CowSegment<'a>>,
+    inserted: usize,
+    length: usize,
+  },
+}
+
+/// Statistics about a built query, returned by [`QueryBuilder::build_with_stats()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BuildStats {
+  /// The number of segments the query is made of.
+  pub segment_count: usize,
+
+  /// The number of values bound with [`QueryBuilder::bind()`].
+  pub binding_count: usize,
+
+  /// The length in bytes of the built query.
+  pub output_length: usize,
+}
+
+/// The optional checks of [`QueryBuilder::try_build_with()`], run on top of the
+/// validation of the clauses.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BuildOptions {
+  /// Reject the queries using `$parameters` that are not bound with
+  /// [`QueryBuilder::bind()`], except the ones SurrealDB defines itself like
+  /// `$this` or `$before`.
+  pub deny_unbound_parameters: bool,
+
+  /// Reject the queries where text was inserted with [`QueryBuilder::raw()`],
+  /// [`QueryBuilder::extend_segments()`], [`QueryBuilder::param()`] or the
+  /// [query!](crate::query) macro, for the codebases where every value must be
+  /// bound. Note that the text passed to the other methods is not tracked.
+  pub deny_raw_text: bool,
+
+  /// Reject the statements that have the same clause twice, like two WHERE or
+  /// two LIMIT clauses.
+  pub deny_duplicate_clauses: bool,
+
+  /// Collapse the whitespace of the query into single spaces and remove the
+  /// spaces around the commas, semicolons and brackets, to send smaller
+  /// queries and get stable snapshots. The string literals are left untouched.
+  pub compact: bool,
+
+  /// Reject the queries longer than this many bytes, once rendered.
+  pub max_output_length: Option<usize>,
+
+  /// Reject the queries made of more segments than this, before they are even
+  /// rendered. It protects against the queries growing with the user input,
+  /// like an `IN` list of unbounded length built with [`QueryBuilder::commas()`].
+  pub max_segment_count: Option<usize>,
+
+  /// The version of SurrealDB the query targets, the statements and paths it
+  /// does not support are rejected. This is the only place the dialect of a
+  /// query is enforced, the builder methods and the node builders always write
+  /// their syntax whatever the targeted version.
+  pub dialect: Dialect,
+}
+
+/// The state of a [QueryBuilder] recorded by [`QueryBuilder::checkpoint()`], to
+/// restore it later with [`QueryBuilder::rollback_to()`].
+///
+/// Only the number of segments, hooks and changes to the parameters and
+/// bindings is recorded. The builder keeps the values its parameters and
+/// bindings replace, and the segments its conditions are inserted among, so the
+/// ones replaced after the checkpoint are restored as well.
+#[derive(Debug, Clone, Copy)]
+pub struct Checkpoint {
+  segment_count: usize,
+  build_hook_count: usize,
+  segment_hook_count: usize,
+  raw_text_count: usize,
+  change_count: usize,
 }
 
 impl<'a> QueryBuilder<'a> {
@@ -16,6 +132,11 @@ impl<'a> QueryBuilder<'a> {
     QueryBuilder {
       segments: Vec::new(),
       parameters: HashMap::new(),
+      bindings: Bindings::new(),
+      build_hooks: Vec::new(),
+      segment_hooks: Vec::new(),
+      raw_text_methods: Vec::new(),
+      changes: Vec::new(),
     }
   }
 
@@ -85,7 +206,9 @@ impl<'a> QueryBuilder<'a> {
   /// RELATE user:tobie->write->article:surreal SET time.written = time::now();
   /// ```
   /// _Note: the `SET` or anything after it in the example above should be added
-  /// manually using the [`QueryBuilder::set()`] method._
+  /// manually using the [`QueryBuilder::set()`] method, or the
+  /// [`QueryBuilder::set_parameterized()`] and [`QueryBuilder::set_model()`] methods
+  /// to set the properties of the edge._
   ///
   /// # Example
   /// ```
@@ -101,6 +224,36 @@ impl<'a> QueryBuilder<'a> {
     self
   }
 
+  /// Start a `RELATE` statement from its three parts, the `from` node, the `edge`
+  /// and the `to` node. Any type that can be displayed can be passed, like models,
+  /// schema fields or parameters, so the relation doesn't have to be built as a
+  /// raw string beforehand.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .relate_nodes("$account", "manage", "$project")
+  ///   .set_parameterized(&["since"])
+  ///   .param("$account", "Account:John")
+  ///   .build();
+  ///
+  /// assert_eq!(
+  ///   query,
+  ///   "RELATE Account:John->manage->$project SET since = $since"
+  /// );
+  /// ```
+  pub fn relate_nodes<F: Display, E: Display, T: Display>(
+    mut self, from: F, edge: E, to: T,
+  ) -> Self {
+    let relation = from.to_string().with(edge).with(to);
+
+    self.add_segment_p("RELATE", relation);
+
+    self
+  }
+
   /// Start a `CONTENT` statement. Content statements often follow RELATE statements:
   /// ```sql
   /// RELATE user:tobie->write->article:surreal CONTENT {
@@ -141,20 +294,131 @@ impl<'a> QueryBuilder<'a> {
     self
   }
 
+  /// Starts a SELECT clause with many fields, nothing is added if there are no
+  /// fields.
+  ///
   /// # Example
   /// ```
   /// use surreal_simple_querybuilder::prelude::*;
   ///
   /// let query = QueryBuilder::new().select_many(&["ee:Person", "o:Order"]).build();
   ///
-  /// assert_eq!(query, "SELECT ee:Person , o:Order")
+  /// assert_eq!(query, "SELECT ee:Person , o:Order");
+  /// assert_eq!("", QueryBuilder::new().select_many::<&str>(&[]).build());
   /// ```
   pub fn select_many<T: Into<CowSegment<'a>>>(mut self, nodes: &[T]) -> Self
   where
     T: Copy,
   {
-    self.add_segment("SELECT");
-    self.join_segments(",", "", nodes, "");
+    self.add_list("SELECT", nodes, "");
+
+    self
+  }
+
+  /// Start a query counting the rows of the `target`, the conditions can be
+  /// passed on the target itself. The number can be read with
+  /// [`QueryBuilder::fetch_count()`] once the query is executed.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new().count_from("Account").build();
+  ///
+  /// assert_eq!(query, "SELECT count() FROM Account GROUP ALL");
+  /// ```
+  pub fn count_from<T: Into<CowSegment<'a>>>(self, target: T) -> Self {
+    self.select("count()").from(target).segment("GROUP ALL")
+  }
+
+  /// Start a query checking whether any row of the `target` matches the
+  /// `condition`, which stops at the first match. The result can be read with
+  /// [`QueryBuilder::fetch_exists()`] once the query is executed.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .exists("Account", "handle = $handle")
+  ///   .build();
+  ///
+  /// assert_eq!(query, "SELECT VALUE true FROM Account WHERE handle = $handle LIMIT 1");
+  /// ```
+  pub fn exists<T: Into<CowSegment<'a>>, C: Into<CowSegment<'a>>>(
+    self, target: T, condition: C,
+  ) -> Self {
+    self
+      .segment("SELECT VALUE true")
+      .from(target)
+      .filter(condition)
+      .limit(1)
+  }
+
+  /// Start a query fetching the `edge` records between the two nodes, in both
+  /// directions.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .edges_between("Account:john", "follows", "Account:mark")
+  ///   .build();
+  ///
+  /// assert_eq!(
+  ///   query,
+  ///   "SELECT * FROM follows WHERE (in = Account:john AND out = Account:mark) \
+  ///    OR (in = Account:mark AND out = Account:john)"
+  /// );
+  /// ```
+  pub fn edges_between<A: Display, E: Display, B: Display>(
+    self, first: A, edge: E, second: B,
+  ) -> Self {
+    let first = first.to_string();
+    let second = second.to_string();
+    let (first, second) = (escape_record_id(&first), escape_record_id(&second));
+
+    self.select("*").from(edge.to_string()).filter(format!(
+      "(in = {first} AND out = {second}) OR (in = {second} AND out = {first})"
+    ))
+  }
+
+  /// Start a query returning the nodes related to both nodes through the
+  /// `edge`, in either direction.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .shared_neighbors("Account:john", "follows", "Account:mark")
+  ///   .build();
+  ///
+  /// assert_eq!(
+  ///   query,
+  ///   "RETURN array::intersect(\
+  ///    array::union(Account:john->follows->?, Account:john<-follows<-?), \
+  ///    array::union(Account:mark->follows->?, Account:mark<-follows<-?))"
+  /// );
+  /// ```
+  pub fn shared_neighbors<A: Display, E: Display, B: Display>(
+    mut self, first: A, edge: E, second: B,
+  ) -> Self {
+    let neighbors = |node: String| {
+      let node = escape_record_id(&node).into_owned();
+
+      format!("array::union({node}->{edge}->?, {node}<-{edge}<-?)")
+    };
+
+    self.add_segment_p(
+      "RETURN",
+      format!(
+        "array::intersect({}, {})",
+        neighbors(first.to_string()),
+        neighbors(second.to_string())
+      ),
+    );
 
     self
   }
@@ -292,7 +556,8 @@ impl<'a> QueryBuilder<'a> {
     self
   }
 
-  /// Starts a SET clause with many fields.
+  /// Starts a SET clause with many fields, nothing is added if there are no
+  /// fields.
   ///
   /// # Example
   /// ```
@@ -308,8 +573,40 @@ impl<'a> QueryBuilder<'a> {
   where
     T: Copy,
   {
-    self.add_segment("SET");
-    self.join_segments(",", "", updates, "");
+    self.add_list("SET", updates, "");
+
+    self
+  }
+
+  /// Starts a SET clause where every field is assigned the parameter of the same
+  /// name, using [`ToNodeBuilder::equals_parameterized()`]. Pairs well with
+  /// [`QueryBuilder::relate()`] to set the properties of an edge.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .relate("Account:John".with("manage").with("Project:one"))
+  ///   .set_parameterized(&["since", "role"])
+  ///   .build();
+  ///
+  /// assert_eq!(
+  ///   query,
+  ///   "RELATE Account:John->manage->Project:one SET since = $since , role = $role"
+  /// );
+  /// ```
+  pub fn set_parameterized<T: ToNodeBuilder>(mut self, fields: &[T]) -> Self {
+    if fields.is_empty() {
+      return self;
+    }
+
+    let updates: Vec<String> = fields
+      .iter()
+      .map(|field| field.equals_parameterized())
+      .collect();
+
+    self.add_segment_p("SET", updates.join(" , "));
 
     self
   }
@@ -332,7 +629,8 @@ impl<'a> QueryBuilder<'a> {
     self
   }
 
-  /// Starts a FETCH clause with zero or more fields,
+  /// Starts a FETCH clause with zero or more fields, nothing is added if there
+  /// are no fields.
   ///
   /// # Example
   /// ```
@@ -348,8 +646,7 @@ impl<'a> QueryBuilder<'a> {
   where
     T: Copy,
   {
-    self.add_segment("FETCH");
-    self.join_segments(",", "", fields, "");
+    self.add_list("FETCH", fields, "");
 
     self
   }
@@ -372,7 +669,8 @@ impl<'a> QueryBuilder<'a> {
     self
   }
 
-  /// Starts a GROUP BY clause with zero or more fields,
+  /// Starts a GROUP BY clause with zero or more fields, nothing is added if there
+  /// are no fields.
   ///
   /// # Example
   /// ```
@@ -388,8 +686,7 @@ impl<'a> QueryBuilder<'a> {
   where
     T: Copy,
   {
-    self.add_segment("GROUP BY");
-    self.join_segments(",", "", fields, "");
+    self.add_list("GROUP BY", fields, "");
 
     self
   }
@@ -412,7 +709,8 @@ impl<'a> QueryBuilder<'a> {
     self
   }
 
-  /// Starts a ORDER BY ASC clause with zero or more fields,
+  /// Starts a ORDER BY ASC clause with zero or more fields, nothing is added if
+  /// there are no fields.
   ///
   /// # Example
   /// ```
@@ -428,8 +726,7 @@ impl<'a> QueryBuilder<'a> {
   where
     T: Copy,
   {
-    self.add_segment("ORDER BY");
-    self.join_segments(",", "", fields, "ASC");
+    self.add_list("ORDER BY", fields, "ASC");
 
     self
   }
@@ -452,7 +749,8 @@ impl<'a> QueryBuilder<'a> {
     self
   }
 
-  /// Starts a ORDER BY DESC clause with zero or more fields,
+  /// Starts a ORDER BY DESC clause with zero or more fields, nothing is added
+  /// if there are no fields.
   ///
   /// # Example
   /// ```
@@ -468,8 +766,7 @@ impl<'a> QueryBuilder<'a> {
   where
     T: Copy,
   {
-    self.add_segment("ORDER BY");
-    self.join_segments(",", "", fields, "DESC");
+    self.add_list("ORDER BY", fields, "DESC");
 
     self
   }
@@ -510,6 +807,161 @@ impl<'a> QueryBuilder<'a> {
     action(self)
   }
 
+  /// Calls the `action` with the value of the `option` if there is one, so the
+  /// optional values can add their own clauses without breaking the chain. The
+  /// value is moved into the `action`, letting it be written in an owned
+  /// segment or bound to a parameter.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let handle = Some("John".to_owned());
+  /// let limit: Option<u32> = None;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .if_let(handle, |query, handle| {
+  ///     query.filter(format!("handle = {}", escape_str(&handle)))
+  ///   })
+  ///   .if_let(limit, |query, limit| query.limit(limit))
+  ///   .build();
+  ///
+  /// assert_eq!("SELECT * FROM Account WHERE handle = 'John'", query);
+  /// ```
+  pub fn if_let<V, F>(self, option: Option<V>, action: F) -> Self
+  where
+    F: FnOnce(Self, V) -> Self,
+  {
+    match option {
+      Some(value) => action(self, value),
+      None => self,
+    }
+  }
+
+  /// Record the current state of the builder, to go back to it with
+  /// [`QueryBuilder::rollback_to()`] if the clauses added afterwards turn out
+  /// to be unwanted.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account");
+  ///
+  /// let checkpoint = query.checkpoint();
+  /// let query = query
+  ///   .filter("handle = $handle")
+  ///   .bind("handle", "John")
+  ///   .unwrap();
+  ///
+  /// let is_allowed = false;
+  /// let query = if is_allowed {
+  ///   query
+  /// } else {
+  ///   query.rollback_to(checkpoint)
+  /// };
+  ///
+  /// let (query, bindings) = query.build_with_bindings();
+  /// assert_eq!("SELECT * FROM Account", query);
+  /// assert!(bindings.is_empty());
+  /// ```
+  pub fn checkpoint(&self) -> Checkpoint {
+    Checkpoint {
+      segment_count: self.segments.len(),
+      build_hook_count: self.build_hooks.len(),
+      segment_hook_count: self.segment_hooks.len(),
+      raw_text_count: self.raw_text_methods.len(),
+      change_count: self.changes.len(),
+    }
+  }
+
+  /// Restore the state recorded by [`QueryBuilder::checkpoint()`], dropping the
+  /// segments, parameters, bindings and hooks added since then.
+  pub fn rollback_to(mut self, checkpoint: Checkpoint) -> Self {
+    debug_assert!(
+      checkpoint.segment_count <= self.segments.len(),
+      "the checkpoint was not taken from this builder"
+    );
+
+    self.build_hooks.truncate(checkpoint.build_hook_count);
+    self.segment_hooks.truncate(checkpoint.segment_hook_count);
+    self.raw_text_methods.truncate(checkpoint.raw_text_count);
+
+    let count = checkpoint.change_count.min(self.changes.len());
+    for change in self.changes.drain(count..).rev() {
+      match change {
+        Change::Segments {
+          index,
+          replaced,
+          inserted,
+          length,
+        } => {
+          // the segments pushed after the change are dropped first so the
+          // inserted ones are found where they were inserted
+          self.segments.truncate(length);
+          self.segments.splice(index..index + inserted, replaced);
+        }
+        Change::Parameter(key, Some(previous)) => {
+          self.parameters.insert(key, previous);
+        }
+        Change::Parameter(key, None) => {
+          self.parameters.remove(&key);
+        }
+        Change::Binding(key, Some(previous)) => {
+          self.bindings.insert_value(&key, previous);
+        }
+        Change::Binding(key, None) => self.bindings.remove(&key),
+      };
+    }
+
+    self.segments.truncate(checkpoint.segment_count);
+
+    self
+  }
+
+  /// Set the `key` parameter, recording the value it replaces for
+  /// [`QueryBuilder::rollback_to()`].
+  fn insert_parameter(&mut self, key: CowSegment<'a>, value: CowSegment<'a>) {
+    let previous = self.parameters.insert(key.clone(), value);
+    self.changes.push(Change::Parameter(key, previous));
+  }
+
+  /// Replace the segments in `range` with the `segments`, recording the ones it
+  /// replaces for [`QueryBuilder::rollback_to()`].
+  fn splice_segments(&mut self, range: Range<usize>, segments: Vec<CowSegment<'a>>) {
+    let index = range.start;
+    let inserted = segments.len();
+    let replaced = self.segments.splice(range, segments).collect();
+
+    self.changes.push(Change::Segments {
+      index,
+      replaced,
+      inserted,
+      length: self.segments.len(),
+    });
+  }
+
+  /// Serialize the `value` and bind it, see [`QueryBuilder::insert_binding_value()`].
+  fn insert_binding<T: Serialize>(&mut self, key: &str, value: T) -> SqlSerializeResult<()> {
+    self.insert_binding_value(key, crate::bindings::to_value(value)?);
+
+    Ok(())
+  }
+
+  /// Bind the `key` parameter, recording the value it replaces for
+  /// [`QueryBuilder::rollback_to()`].
+  fn insert_binding_value(&mut self, key: &str, value: serde_json::Value) {
+    let previous = self.bindings.insert_value(key, value);
+    self.changes.push(Change::Binding(
+      key.trim_start_matches('$').to_owned(),
+      previous,
+    ));
+  }
+
   /// Writes an AND followed by the supplied `first_condition` and any other
   /// statement added to the querybuilder in the `action` closure surrounded by
   /// parenthesis.
@@ -538,32 +990,200 @@ impl<'a> QueryBuilder<'a> {
     F: Fn(Self) -> Self,
   {
     self.add_segment_p("AND", "(");
+    let opening = self.segments.len() - 1;
     self.add_segment(first_condition);
     let mut output = action(self);
+
+    debug_assert!(
+      output
+        .segments
+        .get(opening)
+        .is_some_and(|segment| segment == "("),
+      "the action of and_group() dropped the opening parenthesis of its group"
+    );
+
     output.add_segment(")");
 
     output
   }
 
-  /// Pushes raw text to the buffer
+  /// Pushes raw text to the buffer, either borrowed or owned like the strings
+  /// built with `format!()`.
   ///
   /// # Example
   /// ```
   /// use surreal_simple_querybuilder::prelude::*;
   ///
+  /// let limit = 10;
   /// let query = QueryBuilder::new()
   ///   .raw("foo bar")
+  ///   .raw(format!("LIMIT {limit}"))
+  ///   .build();
+  ///
+  /// assert_eq!(query, "foo bar LIMIT 10");
+  /// ```
+  pub fn raw<T: Into<CowSegment<'a>>>(mut self, text: T) -> Self {
+    self.add_segment(text);
+    self.mark_raw_text("raw");
+
+    self
+  }
+
+  /// Pushes every item of the iterator to the buffer as raw text, like
+  /// [`QueryBuilder::raw()`] does for a single segment.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let fields = ["handle", "email"];
+  /// let query = QueryBuilder::new()
+  ///   .extend_segments(["SELECT", "*", "FROM", "Account"])
+  ///   .extend_segments(fields.iter().map(|field| format!("FETCH {field}")))
   ///   .build();
   ///
-  /// assert_eq!(query, "foo bar");
+  /// assert_eq!("SELECT * FROM Account FETCH handle FETCH email", query);
+  /// ```
+  pub fn extend_segments<I>(mut self, segments: I) -> Self
+  where
+    I: IntoIterator,
+    I::Item: Into<CowSegment<'a>>,
+  {
+    for segment in segments {
+      self.add_segment(segment);
+      self.mark_raw_text("extend_segments");
+    }
+
+    self
+  }
+
+  /// Calls the `action` once for every item of the iterator, passing the
+  /// builder along, so a list of clauses of any length can be added without
+  /// leaving the chain.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let filters = [("handle", "John"), ("email", "john@mail.com")];
+  ///
+  /// let (query, bindings) = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("true")
+  ///   .for_each(filters, |query, (field, value)| {
+  ///     query.and(format!("{field} = ${field}")).bind(field, value).unwrap()
+  ///   })
+  ///   .build_with_bindings();
+  ///
+  /// assert_eq!(
+  ///   "SELECT * FROM Account WHERE true AND handle = $handle AND email = $email",
+  ///   query
+  /// );
+  /// assert_eq!(2, bindings.len());
   /// ```
-  pub fn raw(mut self, text: &'a str) -> Self {
+  pub fn for_each<I, F>(self, items: I, action: F) -> Self
+  where
+    I: IntoIterator,
+    F: FnMut(Self, I::Item) -> Self,
+  {
+    items.into_iter().fold(self, action)
+  }
+
+  /// Push a segment the crate itself wrote, unlike [`QueryBuilder::raw()`] that
+  /// is tracked by [`BuildOptions::deny_raw_text`].
+  pub(crate) fn segment<T: Into<CowSegment<'a>>>(mut self, segment: T) -> Self {
+    self.add_segment(segment);
+
+    self
+  }
+
+  /// Push the text rendered by the [query!](crate::query) macro, tracked as raw
+  /// text like [`QueryBuilder::raw()`] is.
+  #[doc(hidden)]
+  pub fn raw_from_macro(mut self, text: String) -> Self {
     self.add_segment(text);
+    self.mark_raw_text("query!");
+
+    self
+  }
+
+  fn mark_raw_text(&mut self, method: &'static str) {
+    if !self.raw_text_methods.contains(&method) {
+      self.raw_text_methods.push(method);
+    }
+  }
+
+  /// Add the condition to the WHERE clause of the current statement, or start
+  /// it if the statement has none yet. The clause is written before the clauses
+  /// that must follow it, like an ORDER BY or a LIMIT already in the statement.
+  ///
+  /// The existing conditions and the new one are grouped in parenthesis so an
+  /// OR in one of them does not take over the AND joining them. The existing
+  /// conditions are not grouped again if they already are groups joined with
+  /// AND.
+  pub(crate) fn condition<T: Into<CowSegment<'a>>>(mut self, condition: T) -> Self {
+    let statement = self.statement_start();
+    let end = self.clause_end(statement, crate::clauses::follows_where);
+    let clause = self.segments[statement..end]
+      .iter()
+      .position(|segment| segment == "WHERE")
+      .map(|index| statement + index);
+
+    let Some(clause) = clause else {
+      self.splice_segments(end..end, vec!["WHERE".into(), condition.into()]);
+
+      return self;
+    };
+
+    let mut segments = self.segments[clause + 1..end].to_vec();
+
+    if !crate::clauses::is_conjunction(&segments.join(" ")) {
+      if let Some(first) = segments.first_mut() {
+        *first = format!("({first}").into();
+      }
+
+      if let Some(last) = segments.last_mut() {
+        *last = format!("{last})").into();
+      }
+    }
+
+    segments.push("AND".into());
+    segments.push(format!("({})", condition.into()).into());
+    self.splice_segments(clause + 1..end, segments);
+
+    self
+  }
 
+  /// Return whether the current statement has a WHERE clause.
+  pub(crate) fn has_condition(&self) -> bool {
+    self.segments[self.statement_start()..]
+      .iter()
+      .any(|segment| segment == "WHERE")
+  }
+
+  /// Return the index of the first segment of the current statement, the one
+  /// following the last semicolon.
+  fn statement_start(&self) -> usize {
     self
+      .segments
+      .iter()
+      .rposition(|segment| segment.trim_end().ends_with(';'))
+      .map_or(0, |index| index + 1)
+  }
+
+  /// Return the index of the first segment from `statement` starting a clause
+  /// matched by `follows`, or the end of the query if there is none.
+  fn clause_end(&self, statement: usize, follows: fn(&str) -> bool) -> usize {
+    self.segments[statement..]
+      .iter()
+      .position(|segment| follows(segment))
+      .map_or(self.segments.len(), |index| statement + index)
   }
 
   /// Start a queue where all of the new pushed actions are separated by commas.
+  /// The parameters, bindings and build hooks added inside the queue are kept
+  /// as well.
   ///
   /// # Example
   /// ```
@@ -578,9 +1198,9 @@ impl<'a> QueryBuilder<'a> {
   /// assert_eq!(query, "foo , bar");
   /// ```
   pub fn commas(mut self, action: fn(Self) -> Self) -> Self {
-    let other = action(QueryBuilder::new());
+    let segments = self.absorb(action(QueryBuilder::new()));
 
-    for (index, segment) in other.segments.into_iter().enumerate() {
+    for (index, segment) in segments.into_iter().enumerate() {
       if index <= 0 {
         self.segments.push(segment);
       } else {
@@ -592,108 +1212,951 @@ impl<'a> QueryBuilder<'a> {
     self
   }
 
-  /// Start a LIMIT clause.
+  /// Append the segments of the `other` builder, along with its parameters,
+  /// bindings and build hooks, so reusable fragments like a standard filter can
+  /// be written as builders of their own. The bindings of `other` win over
+  /// the ones bound to the same parameters.
   ///
   /// # Example
   /// ```
   /// use surreal_simple_querybuilder::prelude::*;
   ///
+  /// fn tenant_scope<'a>(tenant: &str) -> QueryBuilder<'a> {
+  ///   QueryBuilder::new()
+  ///     .and("tenant = $tenant")
+  ///     .bind("tenant", tenant)
+  ///     .unwrap()
+  /// }
   ///
-  /// let page_size = 10.to_string();
-  /// let query = QueryBuilder::new()
-  ///   .limit(&page_size)
-  ///   .build();
-  ///
-  /// assert_eq!(query, "LIMIT 10")
+  /// let (query, bindings) = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("handle = $handle")
+  ///   .extend(tenant_scope("acme"))
+  ///   .build_with_bindings();
   ///
+  /// assert_eq!("SELECT * FROM Account WHERE handle = $handle AND tenant = $tenant", query);
+  /// assert_eq!(Some(&serde_json::Value::from("acme")), bindings.get("tenant"));
   /// ```
-  pub fn limit<T: Into<CowSegment<'a>>>(mut self, limit: T) -> Self {
-    self.add_segment_p("LIMIT", limit);
+  pub fn extend(mut self, other: QueryBuilder<'a>) -> Self {
+    let segments = self.absorb(other);
+    self.segments.extend(segments);
 
     self
   }
 
-  /// Start a START AT clause.
+  /// Take the parameters, bindings, hooks and raw text flags of the
+  /// `other` builder, and return its segments for the caller to place.
+  fn absorb(&mut self, other: QueryBuilder<'a>) -> Vec<CowSegment<'a>> {
+    for method in other.raw_text_methods {
+      self.mark_raw_text(method);
+    }
+
+    for (key, value) in other.parameters {
+      self.insert_parameter(key, value);
+    }
+
+    for (key, value) in serde_json::Map::from(other.bindings) {
+      self.insert_binding_value(&key, value);
+    }
+
+    self.build_hooks.extend(other.build_hooks);
+    self.segment_hooks.extend(other.segment_hooks);
+
+    other.segments
+  }
+
+  /// Start a LIMIT clause. Numbers can be passed directly, as well as
+  /// parameters or any other displayable value.
   ///
   /// # Example
   /// ```
   /// use surreal_simple_querybuilder::prelude::*;
   ///
-  ///
-  /// let page_size = 10.to_string();
+  /// let page_size: u64 = 10;
   /// let query = QueryBuilder::new()
-  ///   .start_at(&page_size)
+  ///   .limit(page_size)
   ///   .build();
   ///
-  /// assert_eq!(query, "START AT 10")
-  ///
+  /// assert_eq!(query, "LIMIT 10");
+  /// assert_eq!("LIMIT $limit", QueryBuilder::new().limit("$limit").build());
   /// ```
-  pub fn start_at<T: Into<CowSegment<'a>>>(mut self, offset: T) -> Self {
-    self.add_segment_p("START AT", offset);
+  pub fn limit<T: Display>(mut self, limit: T) -> Self {
+    self.add_segment_p("LIMIT", limit.to_string());
 
     self
   }
 
-  /// Add the given segment to the internal buffer. This is a rather internal
-  /// method that is set public for special cases, you should prefer using the `raw`
-  /// method instead.
-  pub fn add_segment<T: Into<CowSegment<'a>>>(&mut self, segment: T) -> &mut Self {
-    let into = segment.into();
-
-    if into.is_empty() {
-      return self;
-    }
-
-    self.segments.push(into);
+  /// Start a START AT clause. Numbers can be passed directly, as well as
+  /// parameters or any other displayable value.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let offset: usize = 10;
+  /// let query = QueryBuilder::new()
+  ///   .start_at(offset)
+  ///   .build();
+  ///
+  /// assert_eq!(query, "START AT 10")
+  /// ```
+  pub fn start_at<T: Display>(mut self, offset: T) -> Self {
+    self.add_segment_p("START AT", offset.to_string());
 
     self
   }
 
-  fn add_segment_p<T: Into<CowSegment<'a>>>(&mut self, prefix: &'a str, segment: T) -> &mut Self {
-    self.add_segment(prefix).add_segment(segment)
-  }
-
-  fn add_segment_ps<T: Into<CowSegment<'a>>>(
-    &mut self, prefix: &'a str, segment: T, suffix: &'a str,
-  ) -> &mut Self {
-    self.add_segment_p(prefix, segment).add_segment(suffix)
-  }
-
-  /// Add a parameter and its value to the query that will be used to replace all
-  /// occurences of `key` into `value` when the `build` method is called.
-  ///
-  /// **IMPORTANT** Do not use this for user provided data, the input is not sanitized
+  /// Add the LIMIT and START AT clauses selecting the given page, where pages
+  /// are numbered from 1 and hold `per_page` rows each. A zero `page` or
+  /// `per_page`, as a client may send, returns a
+  /// [QueryBuilderError::InvalidPage].
   ///
   /// # Example
   /// ```
   /// use surreal_simple_querybuilder::prelude::*;
   ///
   /// let query = QueryBuilder::new()
-  ///   .select("{{field}}")
+  ///   .select("*")
   ///   .from("Account")
-  ///   .param("{{field}}", "id")
+  ///   .paginate(3, 10)
+  ///   .unwrap()
   ///   .build();
   ///
-  /// assert_eq!("SELECT id FROM Account", query);
+  /// assert_eq!(query, "SELECT * FROM Account LIMIT 10 START AT 20");
+  /// assert!(QueryBuilder::new().paginate(0, 10).is_err());
   /// ```
-  pub fn param(mut self, key: &'a str, value: &'a str) -> Self {
-    self.parameters.insert(key, value);
+  pub fn paginate(self, page: u64, per_page: u64) -> QueryBuilderResult<Self> {
+    check_page(page, per_page)?;
 
-    self
+    Ok(self.add_page(page, per_page))
   }
 
-  pub fn build(self) -> String {
-    let mut output = self.segments.join(" ");
+  /// Add the LIMIT and START AT clauses of a page already checked by
+  /// [check_page].
+  pub(crate) fn add_page(self, page: u64, per_page: u64) -> Self {
+    let offset = (page - 1).saturating_mul(per_page);
 
-    for (key, value) in self.parameters {
-      let key_size = key.len();
+    self.limit(per_page).start_at(offset)
+  }
 
-      while let Some(index) = output.find(key) {
-        output.replace_range(index..index + key_size, value);
-      }
-    }
+  /// Add the WHERE and ORDER BY clauses of a keyset pagination, selecting the
+  /// rows past the `cursor` value of `field` in the given direction. The cursor
+  /// is bound to the `$cursor` parameter, or to `$cursor_2`, `$cursor_3`... if
+  /// it is already bound, and the cursor of the next page can be read from the
+  /// returned rows with [next_cursor](crate::cursor::next_cursor).
+  ///
+  /// The cursor's condition is grouped and added to the WHERE clause if the
+  /// query already has one, and the field is ordered first in its ORDER BY
+  /// clause if it has one. Both clauses are written before the clauses that
+  /// must follow them, so the method can be called anywhere in the statement.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let (query, bindings) = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .after("created_at", 12, Direction::Asc)
+  ///   .unwrap()
+  ///   .limit(20)
+  ///   .build_with_bindings();
+  ///
+  /// assert_eq!(
+  ///   "SELECT * FROM Account WHERE created_at > $cursor ORDER BY created_at ASC LIMIT 20",
+  ///   query
+  /// );
+  /// assert_eq!(Some(&serde_json::json!(12)), bindings.get("cursor"));
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("verified = true OR admin = true")
+  ///   .order_by_asc("handle")
+  ///   .limit(20)
+  ///   .after("created_at", 12, Direction::Asc)
+  ///   .unwrap()
+  ///   .build();
+  ///
+  /// assert_eq!(
+  ///   "SELECT * FROM Account WHERE (verified = true OR admin = true) AND (created_at > $cursor) \
+  ///    ORDER BY created_at ASC , handle ASC LIMIT 20",
+  ///   query
+  /// );
+  /// ```
+  pub fn after<F: Display, T: Serialize>(
+    self, field: F, cursor: T, direction: Direction,
+  ) -> SqlSerializeResult<Self> {
+    let field = field.to_string();
+    let parameter = (1..)
+      .map(|index| match index {
+        1 => "cursor".to_owned(),
+        index => format!("cursor_{index}"),
+      })
+      .find(|parameter| self.bindings.get(parameter).is_none())
+      .unwrap_or_default();
+
+    let mut query = self.condition(format!("{field} {} ${parameter}", direction.operator()));
+    let statement = query.statement_start();
+    let ordering = query.segments[statement..]
+      .iter()
+      .position(|segment| segment == "ORDER BY")
+      .map(|index| statement + index);
+
+    match ordering {
+      Some(ordering) => query.splice_segments(
+        ordering + 1..ordering + 1,
+        vec![field.into(), direction.keyword().into(), ",".into()],
+      ),
+      None => {
+        let end = query.clause_end(statement, crate::clauses::follows_order);
+
+        query.splice_segments(
+          end..end,
+          vec!["ORDER BY".into(), field.into(), direction.keyword().into()],
+        );
+      }
+    };
+
+    query.bind(&parameter, cursor)
+  }
+
+  /// Add the given segment to the internal buffer. This is a rather internal
+  /// method that is set public for special cases, you should prefer using the `raw`
+  /// method instead.
+  pub fn add_segment<T: Into<CowSegment<'a>>>(&mut self, segment: T) -> &mut Self {
+    let into = segment.into();
+
+    if into.is_empty() {
+      return self;
+    }
+
+    self.segments.push(into);
+
+    self
+  }
+
+  /// Add the `keyword` followed by the comma separated `segments` and their
+  /// `suffix`, or nothing at all if there are no segments so no dangling
+  /// keyword is left in the query.
+  fn add_list<T: Into<CowSegment<'a>> + Copy>(
+    &mut self, keyword: &'a str, segments: &[T], suffix: &'a str,
+  ) -> &mut Self {
+    if segments.is_empty() {
+      return self;
+    }
+
+    self.add_segment(keyword);
+    self.join_segments(",", "", segments, suffix)
+  }
+
+  fn add_segment_p<T: Into<CowSegment<'a>>>(&mut self, prefix: &'a str, segment: T) -> &mut Self {
+    self.add_segment(prefix).add_segment(segment)
+  }
+
+  fn add_segment_ps<T: Into<CowSegment<'a>>>(
+    &mut self, prefix: &'a str, segment: T, suffix: &'a str,
+  ) -> &mut Self {
+    self.add_segment_p(prefix, segment).add_segment(suffix)
+  }
+
+  /// Add a parameter and its value to the query that will be used to replace all
+  /// occurences of `key` into `value` when the `build` method is called. The
+  /// values are inserted as is and never searched for other keys.
+  ///
+  /// **IMPORTANT** Do not use this for user provided data, the input is not sanitized
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .select("{{field}}")
+  ///   .from("Account")
+  ///   .param("{{field}}", "id")
+  ///   .build();
+  ///
+  /// assert_eq!("SELECT id FROM Account", query);
+  /// ```
+  pub fn param<K, V>(mut self, key: K, value: V) -> Self
+  where
+    K: Into<CowSegment<'a>>,
+    V: Into<CowSegment<'a>>,
+  {
+    self.insert_parameter(key.into(), value.into());
+    self.mark_raw_text("param");
+
+    self
+  }
+
+  /// Bind the serialized `value` to the `$key` parameter of the query. Unlike
+  /// [`QueryBuilder::param()`] the value is not written into the query but sent
+  /// alongside it to the database, which makes it safe for user provided data.
+  /// The leading `$` of the key is optional.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let (query, bindings) = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("handle = $handle")
+  ///   .bind("handle", "John")
+  ///   .unwrap()
+  ///   .build_with_bindings();
+  ///
+  /// assert_eq!("SELECT * FROM Account WHERE handle = $handle", query);
+  /// assert_eq!(Some(&serde_json::json!("John")), bindings.get("handle"));
+  /// ```
+  pub fn bind<T: Serialize>(mut self, key: &str, value: T) -> SqlSerializeResult<Self> {
+    self.insert_binding(key, value)?;
+
+    Ok(self)
+  }
+
+  /// Same as [`QueryBuilder::bind()`] for a value that is already serialized.
+  pub(crate) fn bind_value(mut self, key: &str, value: serde_json::Value) -> Self {
+    self.insert_binding_value(key, value);
+
+    self
+  }
+
+  /// Return the values bound so far with [`QueryBuilder::bind()`].
+  pub fn bindings(&self) -> &Bindings {
+    &self.bindings
+  }
+
+  /// Return the values bound so far with [`QueryBuilder::bind()`] as a JSON
+  /// object, so the query can be sent over the HTTP `/sql` endpoint of SurrealDB
+  /// with its variables.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let builder = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("handle = $handle")
+  ///   .bind("handle", "John")
+  ///   .unwrap();
+  ///
+  /// let vars = builder.bindings_json();
+  /// assert_eq!(Some(&serde_json::json!("John")), vars.get("handle"));
+  /// ```
+  pub fn bindings_json(&self) -> serde_json::Map<String, serde_json::Value> {
+    self.bindings.to_json()
+  }
+
+  /// Same as [`QueryBuilder::build()`] but also return the values bound with
+  /// [`QueryBuilder::bind()`], ready to be sent to the database.
+  pub fn build_with_bindings(self) -> (String, Bindings) {
+    let (output, bindings, _) = self.finish(None);
+
+    (output, bindings)
+  }
+
+  /// Same as [`QueryBuilder::build()`] but also return statistics about the
+  /// built query.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let (query, stats) = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .build_with_stats();
+  ///
+  /// assert_eq!(4, stats.segment_count);
+  /// assert_eq!(query.len(), stats.output_length);
+  /// ```
+  pub fn build_with_stats(self) -> (String, BuildStats) {
+    let (output, _, stats) = self.finish(None);
+
+    (output, stats)
+  }
+
+  /// Register a hook called with the query and its bindings every time it is
+  /// built, to record metrics, log the queries or enforce policies. The hooks
+  /// are called in the order they were registered.
+  ///
+  /// # Example
+  /// ```
+  /// use std::cell::RefCell;
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let log = RefCell::new(Vec::new());
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .on_build(|query, _bindings| log.borrow_mut().push(query.to_owned()))
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .build();
+  ///
+  /// assert_eq!(vec![query], log.into_inner());
+  /// ```
+  pub fn on_build<F>(mut self, hook: F) -> Self
+  where
+    F: Fn(&str, &Bindings) + 'a,
+  {
+    self.build_hooks.push(Box::new(hook));
+
+    self
+  }
+
+  /// Register a hook transforming the list of segments right before the query
+  /// is rendered, so cross-cutting rules like the scoping of every query to a
+  /// tenant or the renaming of the tables per environment can be written once
+  /// and applied to every builder, for example through a [QueryFragment](crate::fragment::QueryFragment).
+  /// The hooks run in the order they were registered, before the validation
+  /// of [`QueryBuilder::try_build()`].
+  ///
+  /// The segments are split into words before the hooks run, so the text of a
+  /// [query!](crate::query) or of a segment holding a whole clause, like the
+  /// ones of [SurrealCrud](crate::crud::SurrealCrud), is seen the same way as the
+  /// segments of the builder methods. The string literals are never split.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// fn staging<'a>(query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+  ///   query.transform_segments(|segments| {
+  ///     for segment in segments.iter_mut() {
+  ///       if segment == "Account" {
+  ///         *segment = "staging_Account".into();
+  ///       }
+  ///     }
+  ///   })
+  /// }
+  ///
+  /// let query = staging(QueryBuilder::new())
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .build();
+  ///
+  /// assert_eq!("SELECT * FROM staging_Account", query);
+  /// ```
+  pub fn transform_segments<F>(mut self, hook: F) -> Self
+  where
+    F: FnOnce(&mut Vec<Cow<'a, str>>) + 'a,
+  {
+    self.segment_hooks.push(Box::new(hook));
+
+    self
+  }
+
+  /// Split the segments into words then run the segment hooks over them, once.
+  fn transform(&mut self) {
+    if !self.segment_hooks.is_empty() {
+      self.segments = std::mem::take(&mut self.segments)
+        .into_iter()
+        .flat_map(|segment| match segment {
+          Cow::Borrowed(text) => crate::clauses::words(text)
+            .into_iter()
+            .map(Cow::Borrowed)
+            .collect::<Vec<_>>(),
+          Cow::Owned(text) => crate::clauses::words(&text)
+            .into_iter()
+            .map(|word| Cow::Owned(word.to_owned()))
+            .collect(),
+        })
+        .collect();
+    }
+
+    for hook in std::mem::take(&mut self.segment_hooks) {
+      hook(&mut self.segments);
+    }
+
+    self.segments.retain(|segment| !segment.is_empty());
+  }
+
+  /// Same as [`QueryBuilder::build_with_bindings()`] but bundle the query text
+  /// and its bindings into a single [Query].
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("handle = $handle")
+  ///   .bind("handle", "John")
+  ///   .unwrap()
+  ///   .build_query();
+  ///
+  /// assert_eq!("SELECT * FROM Account WHERE handle = $handle", query.to_string());
+  /// assert_eq!(1, query.bindings().len());
+  /// ```
+  pub fn build_query(self) -> Query {
+    self.into()
+  }
+
+  /// Build the query and run it with its bindings through the supplied `runner`,
+  /// returning every row it yielded.
+  pub async fn fetch_all<T, R>(self, runner: &R) -> Result<Vec<T>, R::Error>
+  where
+    T: DeserializeOwned,
+    R: QueryRunner,
+  {
+    let (query, bindings) = self.build_with_bindings();
+
+    runner.execute(&query, &bindings).await
+  }
+
+  /// Build the query and run it with its bindings through the supplied `runner`,
+  /// returning the first row it yielded if any.
+  pub async fn fetch_one<T, R>(self, runner: &R) -> Result<Option<T>, R::Error>
+  where
+    T: DeserializeOwned,
+    R: QueryRunner,
+  {
+    let rows = self.fetch_all(runner).await?;
+
+    Ok(rows.into_iter().next())
+  }
+
+  /// Build the query and run it with its bindings through the supplied `runner`,
+  /// returning the number in the `count` field of the first row it yielded, or
+  /// zero if there are no rows. Meant for the queries started with
+  /// [`QueryBuilder::count_from()`].
+  pub async fn fetch_count<R: QueryRunner>(self, runner: &R) -> Result<u64, R::Error> {
+    #[derive(Deserialize)]
+    struct CountRow {
+      count: u64,
+    }
+
+    let row: Option<CountRow> = self.fetch_one(runner).await?;
+
+    Ok(row.map_or(0, |row| row.count))
+  }
+
+  /// Build the query and run it with its bindings through the supplied `runner`,
+  /// returning whether it yielded any row. Meant for the queries started with
+  /// [`QueryBuilder::exists()`].
+  pub async fn fetch_exists<R: QueryRunner>(self, runner: &R) -> Result<bool, R::Error> {
+    let row: Option<serde::de::IgnoredAny> = self.fetch_one(runner).await?;
+
+    Ok(row.is_some())
+  }
+
+  /// Pair the query with the type of the rows it returns, so the query and its
+  /// deserialization target are declared together. See [TypedQuery].
+  pub fn into_typed<T: DeserializeOwned>(self) -> TypedQuery<'a, T> {
+    TypedQuery {
+      builder: self,
+      row: PhantomData,
+    }
+  }
+
+  pub fn build(self) -> String {
+    let (output, _, _) = self.finish(None);
+
+    output
+  }
+
+  /// Same as [`QueryBuilder::build()`] but check the structure of the statements
+  /// first, returning an error instead of a query the database would reject
+  /// when a statement has a clause it does not accept, when its clauses are
+  /// not written in the expected order, or when a graph arrow leads nowhere.
+  /// The build hooks only run for the valid queries.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("handle = $handle")
+  ///   .try_build();
+  ///
+  /// assert_eq!(Ok("SELECT * FROM Account WHERE handle = $handle".to_owned()), query);
+  ///
+  /// let error = QueryBuilder::new()
+  ///   .select("*")
+  ///   .filter("handle = $handle")
+  ///   .from("Account")
+  ///   .try_build()
+  ///   .unwrap_err();
+  ///
+  /// assert_eq!(
+  ///   "the FROM clause of a SELECT statement must come before its WHERE clause",
+  ///   error.to_string()
+  /// );
+  /// ```
+  pub fn try_build(self) -> QueryBuilderResult<String> {
+    let query = self.try_build_with(&BuildOptions::default())?;
+
+    Ok(query.into_parts().0)
+  }
+
+  /// Same as [`QueryBuilder::try_build()`] with the additional checks enabled in
+  /// the `options`, returning the query along with its bindings.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let options = BuildOptions {
+  ///   deny_unbound_parameters: true,
+  ///   ..Default::default()
+  /// };
+  ///
+  /// let error = QueryBuilder::new()
+  ///   .update("Account")
+  ///   .set("email = $email, updated_by = $auth.id")
+  ///   .filter("handle = $handle")
+  ///   .bind("email", "john@mail.com")
+  ///   .unwrap()
+  ///   .try_build_with(&options)
+  ///   .unwrap_err();
+  ///
+  /// assert_eq!("the parameters $handle are not bound", error.to_string());
+  /// ```
+  pub fn try_build_with(mut self, options: &BuildOptions) -> QueryBuilderResult<Query> {
+    self.transform();
+
+    if let Some(limit) = options.max_segment_count {
+      let count = self.segments.len();
+
+      if count > limit {
+        return Err(QueryBuilderError::TooManySegments { count, limit });
+      }
+    }
+
+    let output = self.render();
+    crate::validation::validate_clauses(&output)?;
+    crate::validation::validate_arrows(&output)?;
+    crate::validation::validate_dialect(&output, options.dialect)?;
+
+    if options.deny_duplicate_clauses {
+      crate::validation::validate_duplicates(&output)?;
+    }
+
+    if options.deny_unbound_parameters {
+      crate::validation::validate_parameters(&output, &self.bindings)?;
+    }
+
+    if options.deny_raw_text && !self.raw_text_methods.is_empty() {
+      return Err(QueryBuilderError::RawText(self.raw_text_methods));
+    }
+
+    let output = if options.compact {
+      crate::clauses::compact(&output)
+    } else {
+      output
+    };
+
+    if let Some(limit) = options.max_output_length {
+      let length = output.len();
+
+      if length > limit {
+        return Err(QueryBuilderError::OutputTooLong { length, limit });
+      }
+    }
+
+    let (text, bindings, _) = self.complete(output);
+
+    Ok(Query::new(text, bindings))
+  }
+
+  /// Build the query and parse it with the parser of SurrealDB, returning the
+  /// query along with its bindings or the syntax error the database would
+  /// return, see [QueryBuilderError::InvalidSyntax].
+  ///
+  /// ```rs
+  /// let query = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("handle = $handle")
+  ///   .validate()?;
+  ///
+  /// let error = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter("handle = ")
+  ///   .validate()
+  ///   .unwrap_err();
+  ///
+  /// assert!(matches!(
+  ///   error,
+  ///   QueryBuilderError::InvalidSyntax { position: Some((1, _)), .. }
+  /// ));
+  /// ```
+  #[cfg(feature = "validate")]
+  pub fn validate(self) -> QueryBuilderResult<Query> {
+    let (text, bindings) = self.build_with_bindings();
+    crate::validation::validate_syntax(&text)?;
+
+    Ok(Query::new(text, bindings))
+  }
+
+  /// Same as [`QueryBuilder::build()`] but render the query over several lines,
+  /// one per clause, with the sub-queries indented. It is meant for logging the
+  /// queries, not for sending them to the database.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("(SELECT ->manage->Project AS projects FROM Account WHERE handle = $handle)")
+  ///   .fetch("projects")
+  ///   .build_pretty();
+  ///
+  /// assert_eq!(
+  ///   "SELECT *
+  /// FROM (
+  ///   SELECT ->manage->Project AS projects
+  ///   FROM Account
+  ///   WHERE handle = $handle
+  /// )
+  /// FETCH projects",
+  ///   query
+  /// );
+  /// ```
+  pub fn build_pretty(self) -> String {
+    crate::clauses::pretty(&self.build(), 0)
+  }
+
+  /// Same as [`QueryBuilder::build()`] but reuse the query rendered by a previous
+  /// build of the same shape, meaning the same segments and parameters, from the
+  /// supplied `cache`. The bound values are not part of the shape, so the builds
+  /// that only differ by their bindings share the same text.
+  ///
+  /// A hit still hashes and compares the segments and clones the cached text, it
+  /// only saves their joining and the replacement of the [`QueryBuilder::param()`]
+  /// parameters. It pays off for the queries with many parameters, and costs
+  /// about as much as [`QueryBuilder::build()`] for the others.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let cache = QueryCache::new();
+  ///
+  /// for handle in ["John", "Mark"] {
+  ///   let (query, bindings) = QueryBuilder::new()
+  ///     .select("*")
+  ///     .from("Account")
+  ///     .filter("handle = $handle")
+  ///     .bind("handle", handle)
+  ///     .unwrap()
+  ///     .build_cached(&cache);
+  ///
+  ///   assert_eq!("SELECT * FROM Account WHERE handle = $handle", query);
+  ///   assert_eq!(Some(&serde_json::Value::from(handle)), bindings.get("handle"));
+  /// }
+  ///
+  /// assert_eq!(1, cache.len());
+  /// ```
+  pub fn build_cached(self, cache: &QueryCache) -> (String, Bindings) {
+    let (output, bindings, _) = self.finish(Some(cache));
+
+    (output, bindings)
+  }
+
+  /// Transform the segments and render the query, then call the build hooks
+  /// before returning it with its bindings and statistics.
+  fn finish(mut self, cache: Option<&QueryCache>) -> (String, Bindings, BuildStats) {
+    self.transform();
+
+    let output = match cache {
+      Some(cache) => {
+        let shape = self.shape();
+
+        cache.get(&shape).unwrap_or_else(|| {
+          let output = self.render();
+          cache.insert(&shape, output.clone());
+
+          output
+        })
+      }
+      None => self.render(),
+    };
+
+    self.complete(output)
+  }
+
+  /// Run the build hooks over the rendered `output` and gather the statistics
+  /// of the build.
+  fn complete(self, output: String) -> (String, Bindings, BuildStats) {
+    for hook in &self.build_hooks {
+      hook(&output, &self.bindings);
+    }
+
+    crate::trace::built(&output, &self.bindings);
+
+    let stats = BuildStats {
+      segment_count: self.segments.len(),
+      binding_count: self.bindings.len(),
+      output_length: output.len(),
+    };
+
+    (output, self.bindings, stats)
+  }
+
+  /// Join the segments and replace the parameters with their values in a single
+  /// pass over the joined text, so the values are never searched for keys
+  /// themselves. The longest key wins when several keys start at the same place.
+  fn render(&self) -> String {
+    debug_assert!(
+      self.segments.iter().all(|segment| !segment.is_empty()),
+      "the query builder holds an empty segment"
+    );
+
+    let text = self.segments.join(" ");
+
+    if self.parameters.is_empty() {
+      return text;
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+
+    while let Some(c) = rest.chars().next() {
+      let parameter = self
+        .parameters
+        .iter()
+        .filter(|(key, _)| !key.is_empty() && rest.starts_with(key.as_ref()))
+        .max_by_key(|(key, _)| key.len());
+
+      match parameter {
+        Some((key, value)) => {
+          output.push_str(value);
+          rest = &rest[key.len()..];
+        }
+        None => {
+          output.push(c);
+          rest = &rest[c.len_utf8()..];
+        }
+      };
+    }
+
+    output
+  }
+
+  /// Return the shape of the query in a [QueryCache], its segments and sorted
+  /// parameters along with their hash.
+  fn shape(&self) -> Shape<'_> {
+    let mut parameters: Vec<(&str, &str)> = self
+      .parameters
+      .iter()
+      .map(|(key, value)| (key.as_ref(), value.as_ref()))
+      .collect();
+    parameters.sort_unstable();
+
+    let segments: Vec<&str> = self.segments.iter().map(AsRef::as_ref).collect();
+
+    let mut hasher = DefaultHasher::new();
+    segments.hash(&mut hasher);
+    parameters.hash(&mut hasher);
+
+    Shape {
+      hash: hasher.finish(),
+      segments,
+      parameters,
+    }
+  }
+
+  /// Start a SELECT statement listing every field stored on the supplied `model`,
+  /// followed by the FROM clause of its table. Unlike a `SELECT *` the fields
+  /// the model does not declare are left out, so the rows of a schemaless table
+  /// still deserialize into the model.
+  ///
+  /// # Example
+  /// ```
+  /// #![allow(incomplete_features)]
+  /// #![feature(generic_const_exprs)]
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// model!(Account {
+  ///   id,
+  ///   pub handle,
+  ///   pub email,
+  /// });
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .select_model(&schema::model)
+  ///   .filter("handle = $handle")
+  ///   .build();
+  ///
+  /// assert_eq!("SELECT id , handle , email FROM Account WHERE handle = $handle", query);
+  /// ```
+  pub fn select_model<M: SchemaModel>(mut self, model: &M) -> Self {
+    let fields: Vec<_> = model
+      .field_names()
+      .iter()
+      .map(|name| escape_field(name))
+      .collect();
+
+    self.add_segment_p("SELECT", fields.join(" , "));
+    self.add_segment_p("FROM", model.to_string());
+
+    self
+  }
+
+  /// Add one `field = $field` condition per field of the supplied `filters` to
+  /// the WHERE clause, grouped and joined with AND, and bind the values of the
+  /// fields to their parameters. The WHERE clause is started if the query has
+  /// none yet.
+  /// The `filters` may be a struct or a map, and its null fields are skipped so
+  /// a struct of optional filters can be passed as is. Nothing is added if
+  /// every field is null.
+  ///
+  /// The conditions are written in the order of the field names.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// #[derive(serde::Serialize)]
+  /// struct Search {
+  ///   handle: Option<String>,
+  ///   email: Option<String>,
+  ///   verified: Option<bool>,
+  /// }
+  ///
+  /// let search = Search {
+  ///   handle: Some("John".to_owned()),
+  ///   email: None,
+  ///   verified: Some(true),
+  /// };
+  ///
+  /// let (query, bindings) = QueryBuilder::new()
+  ///   .select("*")
+  ///   .from("Account")
+  ///   .filter_object(&search)
+  ///   .unwrap()
+  ///   .build_with_bindings();
+  ///
+  /// assert_eq!(
+  ///   "SELECT * FROM Account WHERE (handle = $handle) AND (verified = $verified)",
+  ///   query
+  /// );
+  /// assert_eq!(2, bindings.len());
+  /// ```
+  pub fn filter_object<T: Serialize>(mut self, filters: &T) -> SqlSerializeResult<Self> {
+    let fields = to_object(filters)?;
+
+    for (field, value) in fields {
+      if value.is_null() {
+        continue;
+      }
+
+      let parameter = parameter_name(&field);
 
-    output
+      self = self.condition(format!("{} = ${parameter}", escape_field(&field)));
+      self.insert_binding_value(&parameter, value);
+    }
+
+    Ok(self)
   }
 
   /// Start a SET statement with all the public fields in the supplied `T` using
@@ -713,6 +2176,45 @@ impl<'a> QueryBuilder<'a> {
     Ok(self)
   }
 
+  /// Same as [`QueryBuilder::set_model()`] but for an instance rather than a
+  /// model, whose serialized fields are bound to the parameters of the SET
+  /// statement so the clause and its data are added together.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// #[derive(serde::Serialize)]
+  /// struct Account {
+  ///   handle: String,
+  ///   email: String,
+  /// }
+  ///
+  /// let account = Account {
+  ///   handle: "John".to_owned(),
+  ///   email: "john@mail.com".to_owned(),
+  /// };
+  ///
+  /// let (query, bindings) = QueryBuilder::new()
+  ///   .update("Account:John")
+  ///   .set_object_values(&account)
+  ///   .unwrap()
+  ///   .build_with_bindings();
+  ///
+  /// assert_eq!("UPDATE Account:John SET handle = $handle , email = $email", query);
+  /// assert_eq!(Some(&serde_json::Value::from("john@mail.com")), bindings.get("email"));
+  /// ```
+  pub fn set_object_values<T: Serialize>(self, object: &T) -> SqlSerializeResult<Self> {
+    let fields = to_object(object)?;
+    let mut query = self.set_model(object)?;
+
+    for (field, value) in fields {
+      query.insert_binding_value(&field, value);
+    }
+
+    Ok(query)
+  }
+
   /// Start an UPDATE statement with all the public fields in the supplied `T` using
   /// the [SqlFieldSerializer] and Serde to list all the serializable fields in order
   /// to get a statement like the following:
@@ -729,4 +2231,689 @@ impl<'a> QueryBuilder<'a> {
 
     Ok(self)
   }
+
+  /// Add an `INSERT INTO` statement inserting every value of the iterator into
+  /// the `table` at once, the values are written as SurrealQL literals. Nothing
+  /// is added if the iterator is empty.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// #[derive(serde::Serialize)]
+  /// struct Account {
+  ///   handle: &'static str,
+  /// }
+  ///
+  /// let accounts = ["John", "Mark"].map(|handle| Account { handle });
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .insert_many("Account", accounts)
+  ///   .unwrap()
+  ///   .build();
+  ///
+  /// assert_eq!("INSERT INTO Account [{ handle: 'John' }, { handle: 'Mark' }]", query);
+  /// ```
+  pub fn insert_many<N: Display, T: Serialize>(
+    self, table: N, values: impl IntoIterator<Item = T>,
+  ) -> SqlSerializeResult<Self> {
+    self.insert_many_chunked(table, values, usize::MAX)
+  }
+
+  /// Add as many `INSERT INTO` statements as needed to insert every value of the
+  /// iterator into the `table` with at most `chunk_size` values per statement,
+  /// to keep the statements of very large batches to a reasonable size.
+  ///
+  /// # Panics
+  /// Panics if `chunk_size` is zero.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .insert_many_chunked("Release", [1, 2, 3], 2)
+  ///   .unwrap()
+  ///   .build();
+  ///
+  /// assert_eq!("INSERT INTO Release [1, 2] ; INSERT INTO Release [3]", query);
+  /// ```
+  pub fn insert_many_chunked<N: Display, T: Serialize>(
+    mut self, table: N, values: impl IntoIterator<Item = T>, chunk_size: usize,
+  ) -> SqlSerializeResult<Self> {
+    assert!(chunk_size > 0, "a chunk must hold at least one value");
+
+    let literals = values
+      .into_iter()
+      .map(|value| crate::literal::literal(&value))
+      .collect::<SqlSerializeResult<Vec<_>>>()?;
+
+    let table = table.to_string();
+    let table = escape_field(&table);
+
+    for (i, chunk) in literals.chunks(chunk_size).enumerate() {
+      if i > 0 {
+        self.add_segment(";");
+      }
+
+      self.add_segment(format!("INSERT INTO {table} [{}]", chunk.join(", ")));
+    }
+
+    Ok(self)
+  }
+
+  /// Add the statement written by `statement` for the `field IN $field`
+  /// condition, with the `values` bound as a single array. Past `chunk_size`
+  /// values, the statement is rather repeated once per chunk of values,
+  /// separated by semicolons, with every chunk bound to a numbered parameter
+  /// like `$field_0`, so very large lists never end up in a single expression.
+  ///
+  /// # Panics
+  /// Panics if `chunk_size` is zero.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let select = |query: QueryBuilder<'static>, condition| {
+  ///   query.select("*").from("Account").filter(condition)
+  /// };
+  ///
+  /// let (query, _) = QueryBuilder::new()
+  ///   .chunked_in("id", &["Account:a", "Account:b"], 2, select)
+  ///   .unwrap()
+  ///   .build_with_bindings();
+  ///
+  /// assert_eq!("SELECT * FROM Account WHERE id IN $id", query);
+  ///
+  /// let (query, bindings) = QueryBuilder::new()
+  ///   .chunked_in("id", &["Account:a", "Account:b", "Account:c"], 2, select)
+  ///   .unwrap()
+  ///   .build_with_bindings();
+  ///
+  /// assert_eq!(
+  ///   "SELECT * FROM Account WHERE id IN $id_0 ; SELECT * FROM Account WHERE id IN $id_1",
+  ///   query
+  /// );
+  /// assert_eq!(Some(&serde_json::json!(["Account:c"])), bindings.get("id_1"));
+  /// ```
+  pub fn chunked_in<T, F>(
+    mut self, field: &str, values: &[T], chunk_size: usize, statement: F,
+  ) -> SqlSerializeResult<Self>
+  where
+    T: Serialize,
+    F: Fn(Self, String) -> Self,
+  {
+    assert!(chunk_size > 0, "a chunk must hold at least one value");
+
+    let parameter = parameter_name(field);
+
+    if values.len() <= chunk_size {
+      self = statement(self, format!("{field} IN ${parameter}"));
+      self.insert_binding(&parameter, values)?;
+
+      return Ok(self);
+    }
+
+    for (i, chunk) in values.chunks(chunk_size).enumerate() {
+      if i > 0 {
+        self.add_segment(";");
+      }
+
+      let parameter = format!("{parameter}_{i}");
+
+      self = statement(self, format!("{field} IN ${parameter}"));
+      self.insert_binding(&parameter, chunk)?;
+    }
+
+    Ok(self)
+  }
+
+  /// Start a `DEFINE NAMESPACE` statement, the name is escaped if needed. See
+  /// [`QueryBuilder::comment()`] to describe the namespace.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new()
+  ///   .define_namespace("acme")
+  ///   .comment("The namespace of the Acme tenant")
+  ///   .build();
+  ///
+  /// assert_eq!("DEFINE NAMESPACE acme COMMENT 'The namespace of the Acme tenant'", query);
+  /// ```
+  pub fn define_namespace(mut self, name: &str) -> Self {
+    self.add_segment_p("DEFINE NAMESPACE", escape_ident(name).into_owned());
+
+    self
+  }
+
+  /// Start a `DEFINE DATABASE` statement in the namespace currently in use, the
+  /// name is escaped if needed.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// let query = QueryBuilder::new().define_database("app-prod").build();
+  ///
+  /// assert_eq!("DEFINE DATABASE ⟨app-prod⟩", query);
+  /// ```
+  pub fn define_database(mut self, name: &str) -> Self {
+    self.add_segment_p("DEFINE DATABASE", escape_ident(name).into_owned());
+
+    self
+  }
+
+  /// Start a `REMOVE NAMESPACE` statement, the counterpart of
+  /// [`QueryBuilder::define_namespace()`].
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// assert_eq!("REMOVE NAMESPACE acme", QueryBuilder::new().remove_namespace("acme").build());
+  /// ```
+  pub fn remove_namespace(mut self, name: &str) -> Self {
+    self.add_segment_p("REMOVE NAMESPACE", escape_ident(name).into_owned());
+
+    self
+  }
+
+  /// Start a `REMOVE DATABASE` statement, the counterpart of
+  /// [`QueryBuilder::define_database()`].
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// assert_eq!("REMOVE DATABASE app", QueryBuilder::new().remove_database("app").build());
+  /// ```
+  pub fn remove_database(mut self, name: &str) -> Self {
+    self.add_segment_p("REMOVE DATABASE", escape_ident(name).into_owned());
+
+    self
+  }
+
+  /// Start a `USE` statement switching to the given namespace and database, so
+  /// the statements that follow it apply to them.
+  ///
+  /// # Example
+  /// ```
+  /// use surreal_simple_querybuilder::prelude::*;
+  ///
+  /// assert_eq!("USE NS acme DB app", QueryBuilder::new().use_database("acme", "app").build());
+  /// ```
+  pub fn use_database(mut self, namespace: &str, database: &str) -> Self {
+    self.add_segment_p("USE NS", escape_ident(namespace).into_owned());
+    self.add_segment_p("DB", escape_ident(database).into_owned());
+
+    self
+  }
+
+  /// Add a `COMMENT` clause to the current DEFINE statement, the text is
+  /// escaped into a string literal.
+  pub fn comment(mut self, text: &str) -> Self {
+    self.add_segment_p("COMMENT", escape_str(text));
+
+    self
+  }
+}
+
+/// Lists the segments of the query before they are joined, the parameters in
+/// the order of their keys, the bindings, and the number of hooks.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let query = QueryBuilder::new()
+///   .select("{{field}}")
+///   .from("Account")
+///   .param("{{field}}", "handle");
+///
+/// assert_eq!(
+///   r#"QueryBuilder { segments: ["SELECT", "{{field}}", "FROM", "Account"], parameters: {"{{field}}": "handle"}, bindings: Bindings { values: {} }, build_hooks: 0, segment_hooks: 0, raw_text_methods: ["param"] }"#,
+///   format!("{query:?}")
+/// );
+/// ```
+impl<'a> std::fmt::Debug for QueryBuilder<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let parameters: std::collections::BTreeMap<_, _> = self.parameters.iter().collect();
+
+    f.debug_struct("QueryBuilder")
+      .field("segments", &self.segments)
+      .field("parameters", &parameters)
+      .field("bindings", &self.bindings)
+      .field("build_hooks", &self.build_hooks.len())
+      .field("segment_hooks", &self.segment_hooks.len())
+      .field("raw_text_methods", &self.raw_text_methods)
+      .finish()
+  }
+}
+
+/// Return the name of the parameter bound to the field, where everything but
+/// the letters and digits is replaced with underscores.
+pub(crate) fn parameter_name(field: &str) -> String {
+  field
+    .chars()
+    .map(|c| if c.is_alphanumeric() { c } else { '_' })
+    .collect()
+}
+
+/// Confirm the page exists, pages are numbered from 1 and hold at least one row.
+pub(crate) fn check_page(page: u64, per_page: u64) -> QueryBuilderResult<()> {
+  if page == 0 || per_page == 0 {
+    return Err(QueryBuilderError::InvalidPage { page, per_page });
+  }
+
+  Ok(())
+}
+
+/// Serialize the value into the fields of a JSON object, for the methods that
+/// turn every field into a bound parameter.
+fn to_object<T: Serialize>(
+  value: &T,
+) -> SqlSerializeResult<serde_json::Map<String, serde_json::Value>> {
+  let value = serde_json::to_value(value).map_err(|e| SqlSerializeError::Message(e.to_string()))?;
+
+  match value {
+    serde_json::Value::Object(fields) => Ok(fields),
+    _ => Err(SqlSerializeError::Message(
+      "the value must serialize into an object".to_owned(),
+    )),
+  }
+}
+
+/// The shape of a query in a [QueryCache]: its segments and sorted parameters,
+/// and the hash the cache is keyed by.
+struct Shape<'s> {
+  hash: u64,
+  segments: Vec<&'s str>,
+  parameters: Vec<(&'s str, &'s str)>,
+}
+
+/// A query rendered in a [QueryCache], along with the shape it was rendered
+/// from so the shapes whose hashes collide are told apart.
+#[derive(Debug)]
+struct CachedQuery {
+  segments: Vec<String>,
+  parameters: Vec<(String, String)>,
+  rendered: String,
+
+  /// The value of the clock when the query was last used.
+  last_use: u64,
+}
+
+impl CachedQuery {
+  fn matches(&self, shape: &Shape) -> bool {
+    let segments = self.segments.iter().map(String::as_str);
+    let parameters = self
+      .parameters
+      .iter()
+      .map(|(key, value)| (key.as_str(), value.as_str()));
+
+    segments.eq(shape.segments.iter().copied()) && parameters.eq(shape.parameters.iter().copied())
+  }
+}
+
+/// An opt-in cache of rendered queries shared by the builders passed to
+/// [`QueryBuilder::build_cached()`], so the repeated builds of a same query
+/// reuse its text. See the method for what a hit saves.
+///
+/// The cache holds at most [`QueryCache::DEFAULT_CAPACITY`] shapes unless it is
+/// created with [`QueryCache::with_capacity()`], the least recently used shape
+/// is evicted to make room for a new one.
+#[derive(Debug)]
+pub struct QueryCache {
+  capacity: usize,
+  rendered: Mutex<CacheEntries>,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+  /// Increased on every access, the queries store the value it had when they
+  /// were last used.
+  clock: u64,
+  queries: HashMap<u64, CachedQuery>,
+}
+
+impl Default for QueryCache {
+  fn default() -> Self {
+    Self::with_capacity(Self::DEFAULT_CAPACITY)
+  }
+}
+
+impl QueryCache {
+  /// The number of shapes a cache holds when it is created with
+  /// [`QueryCache::new()`].
+  pub const DEFAULT_CAPACITY: usize = 128;
+
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create a cache holding at most `capacity` shapes, a cache with no capacity
+  /// stores nothing.
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self {
+      capacity,
+      rendered: Mutex::default(),
+    }
+  }
+
+  /// Return the number of query shapes in the cache.
+  pub fn len(&self) -> usize {
+    self.rendered().queries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.rendered().queries.is_empty()
+  }
+
+  /// Remove every query from the cache.
+  pub fn clear(&self) {
+    self.rendered().queries.clear();
+  }
+
+  /// Return the query rendered from the `shape`, a query stored under the same
+  /// hash but rendered from another shape is a miss.
+  fn get(&self, shape: &Shape) -> Option<String> {
+    let mut entries = self.rendered();
+    entries.clock += 1;
+
+    let clock = entries.clock;
+    let query = entries
+      .queries
+      .get_mut(&shape.hash)
+      .filter(|query| query.matches(shape))?;
+    query.last_use = clock;
+
+    Some(query.rendered.clone())
+  }
+
+  /// Store the query rendered from the `shape`, it replaces the query stored
+  /// under the same hash if any.
+  fn insert(&self, shape: &Shape, rendered: String) {
+    if self.capacity == 0 {
+      return;
+    }
+
+    let mut entries = self.rendered();
+    entries.clock += 1;
+
+    if entries.queries.len() >= self.capacity && !entries.queries.contains_key(&shape.hash) {
+      let least_recent = entries
+        .queries
+        .iter()
+        .min_by_key(|(_, query)| query.last_use)
+        .map(|(hash, _)| *hash);
+
+      if let Some(least_recent) = least_recent {
+        entries.queries.remove(&least_recent);
+      }
+    }
+
+    let query = CachedQuery {
+      segments: shape
+        .segments
+        .iter()
+        .map(|segment| segment.to_string())
+        .collect(),
+      parameters: shape
+        .parameters
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect(),
+      rendered,
+      last_use: entries.clock,
+    };
+
+    entries.queries.insert(shape.hash, query);
+  }
+
+  fn rendered(&self) -> std::sync::MutexGuard<'_, CacheEntries> {
+    // the map stays consistent even if a thread panicked while holding the lock
+    self
+      .rendered
+      .lock()
+      .unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+}
+
+/// The text of a query and the values bound to its parameters, so they can
+/// travel together through the layers of an application.
+///
+/// It serializes into a `{ "text": ..., "bindings": ... }` object, for example to
+/// hand it to JavaScript.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Query {
+  text: String,
+  bindings: Bindings,
+}
+
+impl Query {
+  pub fn new(text: impl Into<String>, bindings: Bindings) -> Self {
+    Self {
+      text: text.into(),
+      bindings,
+    }
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn bindings(&self) -> &Bindings {
+    &self.bindings
+  }
+
+  /// Consume the query and return its text and bindings.
+  pub fn into_parts(self) -> (String, Bindings) {
+    (self.text, self.bindings)
+  }
+
+  /// Parse the text of the query with the parser of SurrealDB, see
+  /// [`QueryBuilder::validate()`].
+  #[cfg(feature = "validate")]
+  pub fn validate(&self) -> QueryBuilderResult<()> {
+    crate::validation::validate_syntax(&self.text)
+  }
+
+  /// Run the query through the supplied `runner`, returning every row it
+  /// yielded.
+  pub async fn fetch_all<T, R>(&self, runner: &R) -> Result<Vec<T>, R::Error>
+  where
+    T: DeserializeOwned,
+    R: QueryRunner,
+  {
+    runner.execute(&self.text, &self.bindings).await
+  }
+}
+
+impl Display for Query {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.text)
+  }
+}
+
+impl<'a> From<QueryBuilder<'a>> for Query {
+  fn from(builder: QueryBuilder<'a>) -> Self {
+    let (text, bindings) = builder.build_with_bindings();
+
+    Self { text, bindings }
+  }
+}
+
+impl<'a> From<QueryBuilder<'a>> for String {
+  fn from(builder: QueryBuilder<'a>) -> Self {
+    builder.build()
+  }
+}
+
+/// Implemented by everything that turns into a [Query], so the functions
+/// taking an `impl IntoQuery` accept a prebuilt string as well as a builder
+/// and its bindings.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// fn log_query(query: impl IntoQuery) -> String {
+///   let query = query.into_query();
+///
+///   format!("{} ({} bindings)", query.text(), query.bindings().len())
+/// }
+///
+/// assert_eq!("INFO FOR DB (0 bindings)", log_query("INFO FOR DB"));
+/// assert_eq!(
+///   "SELECT * FROM Account WHERE handle = $handle (1 bindings)",
+///   log_query(
+///     QueryBuilder::new()
+///       .select("*")
+///       .from("Account")
+///       .filter("handle = $handle")
+///       .bind("handle", "John")
+///       .unwrap()
+///   )
+/// );
+/// ```
+pub trait IntoQuery {
+  fn into_query(self) -> Query;
+}
+
+impl<T: Into<Query>> IntoQuery for T {
+  fn into_query(self) -> Query {
+    self.into()
+  }
+}
+
+impl From<String> for Query {
+  fn from(text: String) -> Self {
+    Self::new(text, Bindings::new())
+  }
+}
+
+impl From<&str> for Query {
+  fn from(text: &str) -> Self {
+    Self::new(text, Bindings::new())
+  }
+}
+
+impl From<Query> for (String, Bindings) {
+  fn from(query: Query) -> Self {
+    query.into_parts()
+  }
+}
+
+impl From<Query> for String {
+  fn from(query: Query) -> Self {
+    query.text
+  }
+}
+
+/// A query paired with the type `T` of the rows it returns, obtained with
+/// [`QueryBuilder::into_typed()`].
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Account {
+///   handle: String,
+/// }
+///
+/// let query: TypedQuery<Account> = QueryBuilder::new()
+///   .select("handle")
+///   .from("Account")
+///   .into_typed();
+///
+/// assert_eq!("SELECT handle FROM Account", query.build());
+/// ```
+pub struct TypedQuery<'a, T> {
+  builder: QueryBuilder<'a>,
+  row: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: DeserializeOwned> TypedQuery<'a, T> {
+  /// Return the builder the query is made from, to keep on adding segments to it.
+  pub fn into_inner(self) -> QueryBuilder<'a> {
+    self.builder
+  }
+
+  pub fn build(self) -> String {
+    self.builder.build()
+  }
+
+  pub fn build_with_bindings(self) -> (String, Bindings) {
+    self.builder.build_with_bindings()
+  }
+
+  /// Run the query through the supplied `runner` and deserialize every row it
+  /// yielded into a `T`.
+  pub async fn fetch_all<R: QueryRunner>(self, runner: &R) -> Result<Vec<T>, R::Error> {
+    self.builder.fetch_all(runner).await
+  }
+
+  /// Run the query through the supplied `runner` and deserialize the first row it
+  /// yielded into a `T`, if any.
+  pub async fn fetch_one<R: QueryRunner>(self, runner: &R) -> Result<Option<T>, R::Error> {
+    self.builder.fetch_one(runner).await
+  }
+}
+
+/// Build a query from a SurrealQL template, where the `{}` are replaced by the
+/// arguments just like with `format!()` and are usually the models and their
+/// fields. The values to bind to the parameters of the query follow a `;`, in
+/// which case the macro returns a [SqlSerializeResult] of the builder.
+///
+/// The braces of the SurrealQL objects must be doubled, like `{{ a: 1 }}`, as
+/// they would otherwise be read as interpolations. The rendered template is raw
+/// text, rejected by [`BuildOptions::deny_raw_text`].
+///
+/// # Example
+/// ```
+/// #![allow(incomplete_features)]
+/// #![feature(generic_const_exprs)]
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// model!(Account {
+///   pub handle,
+/// });
+///
+/// let account = schema::model;
+///
+/// let query = query!("SELECT {} FROM {}", account.handle, account).build();
+/// assert_eq!("SELECT handle FROM Account", query);
+///
+/// let (query, bindings) = query!(
+///   "SELECT * FROM {} WHERE {}",
+///   account,
+///   account.handle.equals_parameterized();
+///   handle = "John"
+/// )
+/// .unwrap()
+/// .fetch("friend")
+/// .build_with_bindings();
+///
+/// assert_eq!("SELECT * FROM Account WHERE handle = $handle FETCH friend", query);
+/// assert_eq!(Some(&serde_json::json!("John")), bindings.get("handle"));
+/// ```
+#[macro_export]
+macro_rules! query {
+  ($template:literal $(, $argument:expr)* $(,)?) => {{
+    $crate::querybuilder::QueryBuilder::new().raw_from_macro(format!($template $(, $argument)*))
+  }};
+
+  ($template:literal $(, $argument:expr)*; $($key:ident = $value:expr),+ $(,)?) => {{
+    let result: $crate::model::SqlSerializeResult<$crate::querybuilder::QueryBuilder> =
+      Ok($crate::query!($template $(, $argument)*));
+
+    $(let result = result.and_then(|builder| builder.bind(stringify!($key), $value));)+
+
+    result
+  }};
 }
@@ -0,0 +1,99 @@
+use std::future::Future;
+
+use serde::de::DeserializeOwned;
+
+use crate::bindings::Bindings;
+
+/// Implemented by the executors able to send a query and its [Bindings] to the
+/// database, and to deserialize the rows it returns.
+///
+/// The crate does not depend on any database client, implementing this trait
+/// over the client or transport of your choice (HTTP, WebSocket, embedded...)
+/// unlocks the [QueryBuilder::fetch_all()](crate::querybuilder::QueryBuilder::fetch_all)
+/// and [QueryBuilder::fetch_one()](crate::querybuilder::QueryBuilder::fetch_one)
+/// helpers. With the `client` feature it is implemented by the `Surreal` client
+/// of the official `surrealdb` crate, whatever its engine.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+/// use serde::de::DeserializeOwned;
+/// # use std::future::Future;
+/// # fn block_on<F: Future>(future: F) -> F::Output {
+/// #   let mut future = std::pin::pin!(future);
+/// #   let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+/// #   loop {
+/// #     if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+/// #       return output;
+/// #     }
+/// #   }
+/// # }
+///
+/// /// A runner that returns the bound handle instead of reaching a database.
+/// struct EchoRunner;
+///
+/// impl QueryRunner for EchoRunner {
+///   type Error = serde_json::Error;
+///
+///   async fn execute<T: DeserializeOwned>(
+///     &self, _query: &str, bindings: &Bindings,
+///   ) -> Result<Vec<T>, Self::Error> {
+///     let rows = bindings.get("handle").cloned().into_iter().collect();
+///
+///     serde_json::from_value(serde_json::Value::Array(rows))
+///   }
+/// }
+///
+/// let handle: Option<String> = block_on(
+///   QueryBuilder::new()
+///     .select("handle")
+///     .from("Account")
+///     .filter("handle = $handle")
+///     .bind("handle", "John")
+///     .unwrap()
+///     .fetch_one(&EchoRunner),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(Some("John".to_owned()), handle);
+/// ```
+pub trait QueryRunner {
+  type Error;
+
+  /// Run the `query` with the supplied `bindings` and deserialize the rows it
+  /// returned.
+  fn execute<T: DeserializeOwned>(
+    &self, query: &str, bindings: &Bindings,
+  ) -> impl Future<Output = Result<Vec<T>, Self::Error>>;
+}
+
+/// The rows are the ones of the last statement of the query, the other
+/// statements are only checked for errors. The record ids are deserialized by
+/// the client, they are `surrealdb::sql::Thing` rather than strings.
+///
+/// ```rs
+/// let db = Surreal::new::<Ws>("localhost:8000").await?;
+/// db.use_ns("test").use_db("test").await?;
+///
+/// let accounts: Vec<Account> = QueryBuilder::new()
+///   .select("*")
+///   .from(account)
+///   .fetch_all(&db)
+///   .await?;
+/// ```
+#[cfg(feature = "client")]
+impl<C: surrealdb::Connection> QueryRunner for surrealdb::Surreal<C> {
+  type Error = surrealdb::Error;
+
+  async fn execute<T: DeserializeOwned>(
+    &self, query: &str, bindings: &Bindings,
+  ) -> Result<Vec<T>, Self::Error> {
+    let future = self.query(query).bind(bindings);
+    let mut response = crate::trace::executing(query, bindings, future)
+      .await?
+      .check()?;
+    let last = response.num_statements().saturating_sub(1);
+
+    response.take(last)
+  }
+}
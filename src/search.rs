@@ -0,0 +1,164 @@
+use std::fmt::Display;
+
+use crate::build_error::QueryBuilderResult;
+use crate::escape::escape_str;
+use crate::querybuilder::check_page;
+use crate::querybuilder::QueryBuilder;
+
+/// Builds a full-text search query, matching the terms against the fields
+/// indexed with a search analyzer and optionally projecting the highlighted
+/// fields, ordering the rows by their relevance score and paginating them.
+///
+/// Every match gets its own reference number in the order they are added, a
+/// row is returned when any of the matches succeeds. The terms are SurrealQL
+/// expressions, usually a parameter bound on the resulting builder.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+///
+/// let (query, bindings) = SearchQuery::new("Article")
+///   .matches("title", "$terms")
+///   .matches("body", "$terms")
+///   .highlight("<b>", "</b>")
+///   .order_by_score()
+///   .paginate(1, 10)
+///   .unwrap()
+///   .build()
+///   .bind("terms", "rust surreal")
+///   .unwrap()
+///   .build_with_bindings();
+///
+/// assert_eq!(
+///   "SELECT *, search::highlight('<b>', '</b>', 1) AS title_highlight, \
+///    search::highlight('<b>', '</b>', 2) AS body_highlight, \
+///    search::score(1) + search::score(2) AS score \
+///    FROM Article WHERE (title @1@ $terms OR body @2@ $terms) \
+///    ORDER BY score DESC LIMIT 10 START AT 0",
+///   query
+/// );
+/// assert_eq!(1, bindings.len());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+  target: String,
+  matches: Vec<(String, String)>,
+  conditions: Vec<String>,
+  highlight: Option<(String, String)>,
+  order_by_score: bool,
+  page: Option<(u64, u64)>,
+}
+
+impl SearchQuery {
+  /// Start a search through the rows of the `target`.
+  pub fn new<T: Display>(target: T) -> Self {
+    Self {
+      target: target.to_string(),
+      matches: Vec::new(),
+      conditions: Vec::new(),
+      highlight: None,
+      order_by_score: false,
+      page: None,
+    }
+  }
+
+  /// Match the `terms` against the `field`, which must be indexed with a
+  /// search analyzer.
+  pub fn matches<F: Display, T: Display>(mut self, field: F, terms: T) -> Self {
+    self.matches.push((field.to_string(), terms.to_string()));
+
+    self
+  }
+
+  /// Add a condition every returned row must satisfy in addition to the matches.
+  pub fn filter<T: Display>(mut self, condition: T) -> Self {
+    self.conditions.push(condition.to_string());
+
+    self
+  }
+
+  /// Project every matched field with its matching terms surrounded by the
+  /// `prefix` and the `suffix`, as a `<field>_highlight` field.
+  pub fn highlight(mut self, prefix: &str, suffix: &str) -> Self {
+    self.highlight = Some((prefix.to_owned(), suffix.to_owned()));
+
+    self
+  }
+
+  /// Project the sum of the relevance scores of the matches as a `score` field,
+  /// and order the rows from the most to the least relevant.
+  pub fn order_by_score(mut self) -> Self {
+    self.order_by_score = true;
+
+    self
+  }
+
+  /// Only return the given page of rows, see [`QueryBuilder::paginate()`].
+  pub fn paginate(mut self, page: u64, per_page: u64) -> QueryBuilderResult<Self> {
+    check_page(page, per_page)?;
+    self.page = Some((page, per_page));
+
+    Ok(self)
+  }
+
+  /// Build the search into a query builder, to bind the terms or add more
+  /// clauses to it.
+  pub fn build<'a>(self) -> QueryBuilder<'a> {
+    let references = 1..=self.matches.len();
+    let mut projections = vec![String::from("*")];
+
+    if let Some((prefix, suffix)) = &self.highlight {
+      let (prefix, suffix) = (escape_str(prefix), escape_str(suffix));
+
+      for ((field, _), reference) in self.matches.iter().zip(references.clone()) {
+        projections.push(format!(
+          "search::highlight({prefix}, {suffix}, {reference}) AS {field}_highlight"
+        ));
+      }
+    }
+
+    if self.order_by_score && !self.matches.is_empty() {
+      let scores: Vec<String> = references
+        .clone()
+        .map(|reference| format!("search::score({reference})"))
+        .collect();
+
+      projections.push(format!("{} AS score", scores.join(" + ")));
+    }
+
+    let matches: Vec<String> = self
+      .matches
+      .iter()
+      .zip(references)
+      .map(|((field, terms), reference)| format!("{field} @{reference}@ {terms}"))
+      .collect();
+
+    // every condition is parenthesized so the `OR` they hold stay within them
+    let mut conditions: Vec<String> = self
+      .conditions
+      .iter()
+      .map(|condition| format!("({condition})"))
+      .collect();
+
+    if !matches.is_empty() {
+      conditions.insert(0, format!("({})", matches.join(" OR ")));
+    }
+
+    let mut query = QueryBuilder::new()
+      .select(projections.join(", "))
+      .from(self.target);
+
+    if !conditions.is_empty() {
+      query = query.filter(conditions.join(" AND "));
+    }
+
+    if self.order_by_score && !self.matches.is_empty() {
+      query = query.order_by_desc("score");
+    }
+
+    match self.page {
+      Some((page, per_page)) => query.add_page(page, per_page),
+      None => query,
+    }
+  }
+}
@@ -0,0 +1,151 @@
+use crate::clauses::top_level_keywords;
+
+/// Normalize the whitespaces of the query so two queries that only differ by
+/// their spacing are equal once normalized: the consecutive whitespaces are
+/// collapsed into a single space, and the spaces around commas and inside
+/// brackets are removed. The string literals are left intact.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::testing::*;
+///
+/// assert_eq!(
+///   "SELECT a,b FROM [1,2] WHERE name = 'a  b'",
+///   normalize_query("SELECT a , b\n  FROM [ 1, 2 ]  WHERE name = 'a  b'")
+/// );
+/// ```
+pub fn normalize_query(query: &str) -> String {
+  let mut output = String::with_capacity(query.len());
+  let mut quote = None;
+  let mut escaped = false;
+  let mut pending_space = false;
+
+  for c in query.chars() {
+    if let Some(opening) = quote {
+      output.push(c);
+
+      match c {
+        _ if escaped => escaped = false,
+        '\\' => escaped = true,
+        _ if c == opening => quote = None,
+        _ => {}
+      };
+
+      continue;
+    }
+
+    if c.is_whitespace() {
+      pending_space = true;
+      continue;
+    }
+
+    let after_tight = output.ends_with(['(', '[', '{', ',']);
+    let before_tight = matches!(c, ')' | ']' | '}' | ',');
+    if pending_space && !output.is_empty() && !after_tight && !before_tight {
+      output.push(' ');
+    }
+
+    pending_space = false;
+
+    if c == '\'' || c == '"' {
+      quote = Some(c);
+    }
+
+    output.push(c);
+  }
+
+  output
+}
+
+/// Return whether the two queries are equal once their whitespaces are
+/// normalized, see [normalize_query()].
+pub fn queries_eq(left: &str, right: &str) -> bool {
+  normalize_query(left) == normalize_query(right)
+}
+
+/// Assert the two queries are equal once their whitespaces are normalized, see
+/// [normalize_query()].
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+/// use surreal_simple_querybuilder::testing::*;
+///
+/// let query = QueryBuilder::new()
+///   .raw("SELECT")
+///   .commas(|query| query.raw("name").raw("email"))
+///   .from("Account")
+///   .build();
+///
+/// assert_query_eq(&query, "SELECT name, email FROM Account");
+/// ```
+#[track_caller]
+pub fn assert_query_eq(left: &str, right: &str) {
+  assert_eq!(normalize_query(left), normalize_query(right));
+}
+
+/// Split the query into its top-level clauses, pairing each clause keyword with
+/// its content. The keywords found in sub-queries and string literals are
+/// ignored, and only the uppercase keywords are clauses so the lowercase fields
+/// named like one, such as `content`, stay in the content of their clause.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::testing::*;
+///
+/// assert_eq!(
+///   vec![
+///     ("SELECT".to_owned(), "*".to_owned()),
+///     ("FROM".to_owned(), "(Account WHERE age > 18)".to_owned()),
+///     ("ORDER BY".to_owned(), "name".to_owned()),
+///   ],
+///   clauses("SELECT * FROM (Account WHERE age > 18) ORDER BY name")
+/// );
+/// ```
+pub fn clauses(query: &str) -> Vec<(String, String)> {
+  let query = normalize_query(query);
+  let keywords = top_level_keywords(&query);
+
+  keywords
+    .iter()
+    .enumerate()
+    .map(|(i, (start, end))| {
+      let next = keywords.get(i + 1).map_or(query.len(), |(start, _)| *start);
+
+      (
+        query[*start..*end].to_owned(),
+        query[*end..next].trim().to_owned(),
+      )
+    })
+    .collect()
+}
+
+/// Assert the query has a top-level `keyword` clause whose content is
+/// `content`, the whitespaces of both being normalized.
+///
+/// # Example
+/// ```
+/// use surreal_simple_querybuilder::prelude::*;
+/// use surreal_simple_querybuilder::testing::*;
+///
+/// let query = QueryBuilder::new()
+///   .select("*")
+///   .from("Account")
+///   .filter("email = $email")
+///   .build();
+///
+/// assert_has_clause(&query, "WHERE", "email = $email");
+/// ```
+#[track_caller]
+pub fn assert_has_clause(query: &str, keyword: &str, content: &str) {
+  let content = normalize_query(content);
+  let clauses = clauses(query);
+  let found = clauses
+    .iter()
+    .any(|(k, c)| k.eq_ignore_ascii_case(keyword) && *c == content);
+
+  assert!(
+    found,
+    "the query `{query}` has no `{keyword} {content}` clause, its clauses are {clauses:?}"
+  );
+}
@@ -0,0 +1,53 @@
+//! The spans and events of the `tracing` feature, the functions do nothing when
+//! the feature is disabled.
+//!
+//! The values of the bindings are never recorded, as they often hold user
+//! data: the queries only refer to them by their `$parameters` and the events
+//! list the names of the bound parameters.
+
+use crate::bindings::Bindings;
+
+/// Emit the event of a built `query`.
+pub(crate) fn built(query: &str, bindings: &Bindings) {
+  #[cfg(feature = "tracing")]
+  tracing::debug!(
+    query = query,
+    bindings = ?bound_parameters(bindings),
+    "built a query"
+  );
+
+  #[cfg(not(feature = "tracing"))]
+  let _ = (query, bindings);
+}
+
+/// Run the `future` executing the `query` inside a span holding the query.
+#[cfg(feature = "client")]
+pub(crate) fn executing<F: std::future::IntoFuture>(
+  query: &str, bindings: &Bindings, future: F,
+) -> impl std::future::Future<Output = F::Output> {
+  #[cfg(feature = "tracing")]
+  {
+    use tracing::Instrument;
+
+    let span = tracing::debug_span!(
+      "executing a query",
+      query = query,
+      bindings = ?bound_parameters(bindings)
+    );
+
+    future.into_future().instrument(span)
+  }
+
+  #[cfg(not(feature = "tracing"))]
+  {
+    let _ = (query, bindings);
+
+    future.into_future()
+  }
+}
+
+/// Return the names of the bound parameters, without their values.
+#[cfg(feature = "tracing")]
+fn bound_parameters(bindings: &Bindings) -> Vec<&str> {
+  bindings.iter().map(|(key, _)| key).collect()
+}
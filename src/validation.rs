@@ -0,0 +1,281 @@
+use crate::bindings::Bindings;
+use crate::build_error::QueryBuilderError;
+use crate::build_error::QueryBuilderResult;
+use crate::clauses::arrows;
+use crate::clauses::contains_code;
+use crate::clauses::keyword_at;
+use crate::clauses::parameters;
+use crate::clauses::statements;
+use crate::clauses::top_level_keywords;
+use crate::dialect::Dialect;
+
+/// The clauses every statement accepts, in the order they must be written. The
+/// clauses sharing a rank can be written in any order.
+const STATEMENT_CLAUSES: &[(&str, &[&[&str]])] = &[
+  (
+    "SELECT",
+    &[
+      &["SELECT"],
+      &["FROM"],
+      &["WHERE"],
+      &["SPLIT", "SPLIT ON"],
+      &["GROUP", "GROUP BY"],
+      &["ORDER", "ORDER BY"],
+      &["LIMIT"],
+      &["START"],
+      &["FETCH"],
+      &["TIMEOUT"],
+      &["PARALLEL"],
+    ],
+  ),
+  (
+    "CREATE",
+    &[
+      &["CREATE"],
+      &["SET", "CONTENT"],
+      &["RETURN"],
+      &["TIMEOUT"],
+      &["PARALLEL"],
+    ],
+  ),
+  (
+    "UPDATE",
+    &[
+      &["UPDATE"],
+      &["SET", "CONTENT", "MERGE"],
+      &["WHERE"],
+      &["RETURN"],
+      &["TIMEOUT"],
+      &["PARALLEL"],
+    ],
+  ),
+  (
+    "UPSERT",
+    &[
+      &["UPSERT"],
+      &["SET", "CONTENT", "MERGE"],
+      &["WHERE"],
+      &["RETURN"],
+      &["TIMEOUT"],
+      &["PARALLEL"],
+    ],
+  ),
+  (
+    "DELETE",
+    &[
+      &["DELETE"],
+      &["FROM"],
+      &["WHERE"],
+      &["RETURN"],
+      &["TIMEOUT"],
+      &["PARALLEL"],
+    ],
+  ),
+  (
+    "RELATE",
+    &[
+      &["RELATE"],
+      &["SET", "CONTENT"],
+      &["RETURN"],
+      &["TIMEOUT"],
+      &["PARALLEL"],
+    ],
+  ),
+  (
+    "INSERT",
+    &[
+      &["INSERT"],
+      &["INTO"],
+      &["UPDATE"],
+      &["RETURN"],
+      &["TIMEOUT"],
+      &["PARALLEL"],
+    ],
+  ),
+];
+
+/// The parameters SurrealDB defines itself, which are never bound.
+const RESERVED_PARAMETERS: &[&str] = &[
+  "access", "after", "auth", "before", "event", "input", "parent", "scope", "session", "this",
+  "token", "value",
+];
+
+/// The clauses each statement accepts, as ranks of clauses.
+type ClauseRanks = &'static [&'static [&'static str]];
+
+/// Return the statements of the query that start with a known statement
+/// keyword, with the clauses they accept and the top-level clauses written
+/// after their first keyword. The other statements, like query fragments, are
+/// skipped.
+fn known_statements(query: &str) -> Vec<(&'static str, ClauseRanks, Vec<&'static str>)> {
+  let mut known = Vec::new();
+
+  for statement in statements(query) {
+    let mut keywords = top_level_keywords(statement)
+      .into_iter()
+      .filter_map(|(start, _)| keyword_at(&statement[start..]));
+
+    let Some(kind) = keywords.next() else {
+      continue;
+    };
+
+    if let Some((name, ranks)) = STATEMENT_CLAUSES.iter().find(|(name, _)| *name == kind) {
+      known.push((*name, *ranks, keywords.collect()));
+    }
+  }
+
+  known
+}
+
+/// Check that the top-level clauses of every statement of the query are
+/// accepted by the statement and are written in the right order.
+pub(crate) fn validate_clauses(query: &str) -> QueryBuilderResult<()> {
+  for (statement, ranks, clauses) in known_statements(query) {
+    let mut latest = (0, statement);
+
+    for clause in clauses {
+      let rank = ranks
+        .iter()
+        .position(|clauses| clauses.contains(&clause))
+        .ok_or(QueryBuilderError::UnexpectedClause { statement, clause })?;
+
+      if rank < latest.0 {
+        return Err(QueryBuilderError::MisplacedClause {
+          statement,
+          clause,
+          following: latest.1,
+        });
+      }
+
+      latest = (rank, clause);
+    }
+  }
+
+  Ok(())
+}
+
+/// Check that no statement of the query has the same top-level clause twice.
+pub(crate) fn validate_duplicates(query: &str) -> QueryBuilderResult<()> {
+  for (statement, _, clauses) in known_statements(query) {
+    for (i, clause) in clauses.iter().enumerate() {
+      if clauses[..i].contains(clause) {
+        return Err(QueryBuilderError::DuplicateClause { statement, clause });
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Check that every parameter the query uses is bound, except the ones reserved
+/// by SurrealDB. The unbound ones are listed once each in the error.
+pub(crate) fn validate_parameters(query: &str, bindings: &Bindings) -> QueryBuilderResult<()> {
+  let mut unbound: Vec<String> = Vec::new();
+
+  for parameter in parameters(query) {
+    let is_bound = bindings.get(parameter).is_some() || RESERVED_PARAMETERS.contains(&parameter);
+    let name = format!("${parameter}");
+
+    if !is_bound && !unbound.contains(&name) {
+      unbound.push(name);
+    }
+  }
+
+  if !unbound.is_empty() {
+    return Err(QueryBuilderError::UnboundParameters(unbound));
+  }
+
+  Ok(())
+}
+
+/// Check that every graph arrow of the query leads somewhere, to an edge or a
+/// node name, a `?`, or a parenthesized filter. The traversals with a dangling
+/// arrow or two arrows in a row are returned in the error.
+pub(crate) fn validate_arrows(query: &str) -> QueryBuilderResult<()> {
+  for (start, end) in arrows(query) {
+    let leads_somewhere = query[end..]
+      .chars()
+      .next()
+      .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '?' | '(' | '⟨' | '`' | '$'));
+
+    if !leads_somewhere {
+      let start = query[..start]
+        .rfind(char::is_whitespace)
+        .map_or(0, |index| index + 1);
+      let end = query[end..]
+        .find(char::is_whitespace)
+        .map_or(query.len(), |index| end + index);
+
+      return Err(QueryBuilderError::MalformedTraversal(
+        query[start..end].to_owned(),
+      ));
+    }
+  }
+
+  Ok(())
+}
+
+/// Check that the statements and paths of the query are supported by the
+/// targeted version of SurrealDB.
+pub(crate) fn validate_dialect(query: &str, dialect: Dialect) -> QueryBuilderResult<()> {
+  let has_upsert = statements(query)
+    .into_iter()
+    .any(|statement| keyword_at(statement) == Some("UPSERT"));
+
+  if has_upsert && !dialect.supports_upsert() {
+    return Err(QueryBuilderError::UnsupportedSyntax {
+      syntax: "UPSERT",
+      dialect,
+    });
+  }
+
+  if contains_code(query, ".{") && !dialect.supports_recursive_paths() {
+    return Err(QueryBuilderError::UnsupportedSyntax {
+      syntax: "recursive path",
+      dialect,
+    });
+  }
+
+  Ok(())
+}
+
+/// Parse the query with the parser of SurrealDB.
+#[cfg(feature = "validate")]
+pub(crate) fn validate_syntax(query: &str) -> QueryBuilderResult<()> {
+  match surrealdb::sql::parse(query) {
+    Ok(_) => Ok(()),
+    Err(error) => {
+      let message = error.to_string();
+
+      Err(QueryBuilderError::InvalidSyntax {
+        position: syntax_position(&message),
+        message,
+      })
+    }
+  }
+}
+
+/// Find the line and column of a syntax error in the `message` of the parser,
+/// written `on line 1 at character 7` by SurrealDB 1.0 and `--> [1:7]` by the
+/// later versions.
+#[cfg(feature = "validate")]
+fn syntax_position(message: &str) -> Option<(usize, usize)> {
+  let number = |text: &str| {
+    let end = text
+      .find(|c: char| !c.is_ascii_digit())
+      .unwrap_or(text.len());
+
+    text[..end].parse().ok()
+  };
+
+  if let Some((_, rest)) = message.split_once("on line ") {
+    let (_, column) = rest.split_once(" at character ")?;
+
+    return Some((number(rest)?, number(column)?));
+  }
+
+  let (_, rest) = message.split_once("--> [")?;
+  let (_, column) = rest.split_once(':')?;
+
+  Some((number(rest)?, number(column)?))
+}
@@ -362,3 +362,2993 @@ fn test_with_id_edge() {
   assert_eq!("Account:an_id->manage->Project:other_id", query_two);
   assert_eq!(query_one, query_two);
 }
+
+#[test]
+fn test_with_id_edge_owned() {
+  let query = account
+    .with_id("an_id")
+    .with(account.managed_projects.with_id("other_id"));
+
+  assert_eq!("Account:an_id->manage->Project:other_id", query);
+}
+
+#[test]
+fn test_model_all_fields() {
+  assert_eq!("friend.*", account.friend.all_fields());
+  assert_eq!("friend.*.*", account.friend.all_fields_nested(2));
+  assert_eq!("->manage->Project.*", account.managed_projects.all_fields());
+
+  let query = QueryBuilder::new()
+    .select(account.friend.all_fields())
+    .from(account)
+    .build();
+
+  assert_eq!(query, "SELECT friend.* FROM Account");
+}
+
+#[test]
+fn test_model_filtered_relations() {
+  assert_eq!(
+    "->manage->Project[WHERE name CONTAINS 'api']",
+    account.managed_projects.filtered("name CONTAINS 'api'")
+  );
+
+  assert_eq!(
+    "->manage->Project->has->Release[WHERE name = $name]",
+    account
+      .managed_projects()
+      .releases
+      .filtered(&release::schema::model.name.equals_parameterized())
+  );
+}
+
+#[test]
+fn test_model_nested_equals_parameterized() {
+  assert_eq!(
+    "friend.handle = $friend_handle",
+    account.friend().handle.equals_parameterized()
+  );
+
+  assert_eq!(
+    "->manage->Project->has->Release.name = $_manage_Project_has_Release_name",
+    account
+      .managed_projects()
+      .releases()
+      .name
+      .equals_parameterized()
+  );
+}
+
+#[test]
+fn test_foreign_vec_mixed_deserialize() {
+  let projects: ForeignVec<Project> = serde_json::from_str(
+    r#"["Project:one", { "id": "Project:two", "name": "two", "releases": [] }]"#,
+  )
+  .unwrap();
+
+  assert_eq!(2, projects.iter().count());
+  assert_eq!(vec!["Project:one"], projects.keys().collect::<Vec<_>>());
+  assert_eq!(
+    vec!["two"],
+    projects
+      .loaded()
+      .map(|loaded| loaded.name.as_str())
+      .collect::<Vec<_>>()
+  );
+
+  assert_eq!(
+    serde_json::to_value(vec!["Project:one", "Project:two"]).unwrap(),
+    serde_json::to_value(&projects).unwrap()
+  );
+
+  let projects: ForeignVec<Project> = serde_json::from_str("null").unwrap();
+  assert!(projects.is_empty());
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+struct RecordKey {
+  tb: String,
+  id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TypedRelease {
+  name: String,
+}
+
+impl IntoKey<RecordKey> for TypedRelease {
+  fn into_key<E>(&self) -> Result<RecordKey, E>
+  where
+    E: serde::ser::Error,
+  {
+    Ok(RecordKey {
+      tb: "Release".to_owned(),
+      id: self.name.clone(),
+    })
+  }
+}
+
+#[test]
+fn test_foreign_typed_key() {
+  let key = RecordKey {
+    tb: "Release".to_owned(),
+    id: "one".to_owned(),
+  };
+
+  let release: Foreign<TypedRelease, RecordKey> =
+    serde_json::from_value(serde_json::to_value(&key).unwrap()).unwrap();
+  assert_eq!(Some(&key), release.key());
+
+  let release: Foreign<TypedRelease, RecordKey> = Foreign::new_value(TypedRelease {
+    name: "one".to_owned(),
+  });
+  assert_eq!(
+    serde_json::to_value(&key).unwrap(),
+    serde_json::to_value(&release).unwrap()
+  );
+
+  let releases: ForeignVec<TypedRelease, RecordKey> =
+    serde_json::from_value(serde_json::to_value(vec![&key]).unwrap()).unwrap();
+  assert_eq!(vec![&key], releases.keys().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_foreign_retained_key() {
+  let mut author: Foreign<Account> = Foreign::new_loaded(
+    "Account:John".to_owned(),
+    Account {
+      handle: "JohnTheUser".to_owned(),
+      ..Default::default()
+    },
+  );
+
+  assert_eq!(
+    Some("JohnTheUser"),
+    author.value().map(|a| a.handle.as_str())
+  );
+  assert_eq!(Some(&"Account:John".to_owned()), author.key());
+
+  // the account has no id, the retained key is serialized without IntoKey
+  assert_eq!(
+    serde_json::to_value("Account:John").unwrap(),
+    serde_json::to_value(&author).unwrap()
+  );
+
+  author.to_key::<serde::de::value::Error>().unwrap();
+  assert!(author.value().is_none());
+  assert_eq!(Some(&"Account:John".to_owned()), author.key());
+}
+
+#[test]
+fn test_foreign_combinators() {
+  let author: Foreign<Account> = Foreign::new_value(Account {
+    handle: "JohnTheUser".to_owned(),
+    ..Default::default()
+  });
+
+  assert!(author.is_loaded());
+  assert!(!author.is_key());
+  assert_eq!(
+    Some(&"JohnTheUser"),
+    author.as_refs().map(|a| a.handle.as_str()).value()
+  );
+
+  let mut key: Foreign<Account> = Foreign::new_key("Account:John".to_owned());
+  assert!(key.is_key());
+  assert!(!key.is_loaded());
+
+  let taken = key.take();
+  assert!(key.is_unloaded());
+  assert_eq!(Some(&"Account:John".to_owned()), taken.key());
+  assert_eq!("", taken.unwrap_or_default().handle);
+  assert_eq!(1, Foreign::<u32>::new().unwrap_or(1));
+
+  assert_eq!("JohnTheUser", author.unwrap_or_default().handle);
+}
+
+#[test]
+fn test_foreign_resolve_key() {
+  let author: Foreign<Account> = Foreign::new_value(Account {
+    id: Some("Account:John".to_owned()),
+    ..Default::default()
+  });
+
+  let key: SqlSerializeResult<Option<String>> = author.resolve_key();
+  assert_eq!(Some("Account:John".to_owned()), key.unwrap());
+
+  // the value is still loaded after obtaining its key
+  assert!(author.is_loaded());
+
+  let author: Foreign<Account> = Foreign::new_value(Account::default());
+  assert!(author.resolve_key::<SqlSerializeError>().is_err());
+
+  let author: Foreign<Account> = Foreign::new();
+  assert_eq!(None, author.resolve_key::<SqlSerializeError>().unwrap());
+}
+
+#[test]
+fn test_foreign_with_loaded_values() {
+  let john = Account {
+    id: Some("Account:John".to_owned()),
+    projects: ForeignVec::new_value(vec![Project {
+      id: Some("Project:one".to_owned()),
+      name: "one".to_owned(),
+      ..Default::default()
+    }]),
+    ..Default::default()
+  };
+
+  let keys = serde_json::to_string(&john).unwrap();
+  assert!(keys.contains(r#""projects":["Project:one"]"#));
+
+  let full = serde_json::to_string(&WithLoadedValues(&john)).unwrap();
+  assert!(full.contains(r#""projects":[{"id":"Project:one","name":"one","releases":[]}]"#));
+
+  // the wrapper only affects the call it was used in
+  assert_eq!(keys, serde_json::to_string(&john).unwrap());
+}
+
+#[test]
+fn test_foreign_null() {
+  #[derive(Serialize)]
+  struct FileUpdate {
+    #[serde(skip_serializing_if = "ForeignKey::is_unloaded")]
+    author: Foreign<Account>,
+  }
+
+  let cleared = FileUpdate {
+    author: Foreign::new_null(),
+  };
+  assert!(cleared.author.is_null());
+  assert!(!cleared.author.is_unloaded());
+  assert_eq!(
+    r#"{"author":null}"#,
+    serde_json::to_string(&cleared).unwrap()
+  );
+
+  let untouched = FileUpdate {
+    author: Foreign::new(),
+  };
+  assert!(!untouched.author.is_null());
+  assert_eq!("{}", serde_json::to_string(&untouched).unwrap());
+
+  let mut author: Foreign<Account> = Foreign::new_key("Account:John".to_owned());
+  author.set_null();
+  assert!(author.is_null());
+  assert!(author.key().is_none());
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Person {
+  id: String,
+  friend: Foreign<Box<Person>>,
+}
+
+impl IntoKey<String> for Person {
+  fn into_key<E>(&self) -> Result<String, E>
+  where
+    E: serde::ser::Error,
+  {
+    Ok(self.id.clone())
+  }
+}
+
+#[test]
+fn test_foreign_boxed_depth() {
+  let json = r#"{"id":"Person:a","friend":{"id":"Person:b","friend":{"id":"Person:c","friend":"Person:d"}}}"#;
+  let a: Person = serde_json::from_str(json).unwrap();
+
+  let b = a.friend.value_unboxed().unwrap();
+  assert_eq!("Person:b", b.id);
+  assert_eq!(
+    Some(&"Person:d".to_owned()),
+    b.friend.value_unboxed().unwrap().friend.key()
+  );
+
+  assert_eq!(
+    serde_json::json!({ "id": "Person:a", "friend": "Person:b" }),
+    serde_json::to_value(&a).unwrap()
+  );
+  assert_eq!(
+    serde_json::json!({ "id": "Person:a", "friend": "Person:b" }),
+    serde_json::to_value(WithLoadedDepth::new(&a, 0)).unwrap()
+  );
+  assert_eq!(
+    serde_json::json!({
+      "id": "Person:a",
+      "friend": { "id": "Person:b", "friend": "Person:c" }
+    }),
+    serde_json::to_value(WithLoadedDepth::new(&a, 1)).unwrap()
+  );
+  assert_eq!(
+    serde_json::from_str::<serde_json::Value>(json).unwrap(),
+    serde_json::to_value(WithLoadedValues(&a)).unwrap()
+  );
+
+  let unboxed: Foreign<Person> = a.friend.unboxed();
+  assert_eq!("Person:b", unboxed.value().unwrap().id);
+}
+
+#[test]
+// the interior mutability of ForeignKey is the serialization flag, which is not
+// part of its hash.
+#[allow(clippy::mutable_key_type)]
+fn test_foreign_comparisons() {
+  let key: Foreign<String> = Foreign::new_key("Account:John".to_owned());
+  let loaded: Foreign<String> = Foreign::new_loaded("Account:John".to_owned(), "John".to_owned());
+
+  // compared by key when one is available
+  assert_eq!(key, loaded);
+  assert_ne!(key, Foreign::new_key("Account:Mark".to_owned()));
+  assert!(key < Foreign::new_key("Account:Mark".to_owned()));
+
+  // and by value otherwise
+  assert_eq!(
+    Foreign::<String>::new_value("John".to_owned()),
+    Foreign::new_value("John".to_owned())
+  );
+  assert_ne!(Foreign::<String>::new(), Foreign::new_null());
+
+  let set: std::collections::HashSet<Foreign<String>> =
+    vec![key, loaded, Foreign::new_key("Account:Mark".to_owned())]
+      .into_iter()
+      .collect();
+
+  assert_eq!(2, set.len());
+}
+
+impl Table for Account {
+  const TABLE: &'static str = "Account";
+}
+
+impl IntoKey<TypedKey<Account>> for Account {
+  fn into_key<E>(&self) -> Result<TypedKey<Account>, E>
+  where
+    E: serde::ser::Error,
+  {
+    let id = self
+      .id
+      .as_ref()
+      .ok_or(serde::ser::Error::custom("The account has no ID"))?;
+
+    TypedKey::new(id.as_str()).map_err(E::custom)
+  }
+}
+
+#[test]
+fn test_foreign_typed_table_key() {
+  #[derive(Debug, Deserialize)]
+  struct Document {
+    author: TypedForeign<Account>,
+  }
+
+  let document: Document = serde_json::from_str(r#"{ "author": "Account:John" }"#).unwrap();
+  assert_eq!("John", document.author.key().unwrap().id());
+
+  let document: Result<Document, _> = serde_json::from_str(r#"{ "author": "Project:one" }"#);
+  assert!(document.is_err());
+
+  assert_eq!(
+    "Account:Mark",
+    TypedKey::<Account>::from_id("Mark").as_str()
+  );
+  assert!(TypedKey::<Account>::new("AccountX:Mark").is_err());
+
+  let author: TypedForeign<Account> = Foreign::new_value(Account {
+    id: Some("Account:John".to_owned()),
+    ..Default::default()
+  });
+  assert_eq!(r#""Account:John""#, serde_json::to_string(&author).unwrap());
+}
+
+#[test]
+fn test_foreign_merge() {
+  let mut author: Foreign<Account> = Foreign::new_key("Account:John".to_owned());
+
+  // imagine the author was fetched from a separate query
+  let fetched: Foreign<Account> = serde_json::from_str(
+    r#"{ "id": "Account:John", "handle": "JohnTheUser", "password": "", "email": "", "projects": [] }"#,
+  )
+  .unwrap();
+
+  author.merge(fetched);
+  assert_eq!("JohnTheUser", author.value().unwrap().handle);
+  assert_eq!(Some(&"Account:John".to_owned()), author.key());
+
+  author.merge(Foreign::new());
+  assert!(author.is_loaded());
+
+  author.merge(Foreign::new_null());
+  assert!(author.is_null());
+}
+
+#[test]
+fn test_foreign_nested_containers() {
+  use std::collections::HashMap;
+
+  let links: HashMap<String, Foreign<Release>> = serde_json::from_str(
+    r#"{ "latest": { "id": "Release:two", "name": "two" }, "previous": "Release:one" }"#,
+  )
+  .unwrap();
+
+  assert_eq!("two", links["latest"].value().unwrap().name);
+  assert_eq!(Some(&"Release:one".to_owned()), links["previous"].key());
+  assert_eq!(
+    vec!["Release:one"],
+    links.foreign_keys().collect::<Vec<_>>()
+  );
+
+  let maybe: Option<Foreign<Release>> = serde_json::from_str("null").unwrap();
+  assert!(maybe.is_none());
+  assert_eq!(0, maybe.foreigns().count());
+
+  let maybe: Option<Foreign<Release>> = serde_json::from_str(r#""Release:one""#).unwrap();
+  assert_eq!(
+    vec!["Release:one"],
+    maybe.foreign_keys().collect::<Vec<_>>()
+  );
+
+  let list: Vec<Option<Foreign<Release>>> =
+    serde_json::from_str(r#"["Release:one", null, { "id": "Release:two", "name": "two" }]"#)
+      .unwrap();
+
+  assert_eq!(2, list.foreigns().count());
+  assert_eq!(vec!["Release:one"], list.foreign_keys().collect::<Vec<_>>());
+  assert_eq!(
+    vec!["two"],
+    list
+      .loaded_values()
+      .map(|release| release.name.as_str())
+      .collect::<Vec<_>>()
+  );
+}
+
+#[test]
+fn test_foreign_deref_option() {
+  let author: Foreign<Account> = Foreign::new_value(Account {
+    handle: "JohnTheUser".to_owned(),
+    ..Default::default()
+  });
+
+  assert!(author.is_some());
+  assert_eq!(
+    Some("JohnTheUser"),
+    (*author).as_ref().map(|a| a.handle.as_str())
+  );
+
+  if let Some(loaded) = &*author {
+    assert_eq!("JohnTheUser", loaded.handle);
+  }
+
+  let option: &Option<Account> = AsRef::as_ref(&author);
+  assert!(option.is_some());
+
+  let key: Foreign<Account> = Foreign::new_key("Account:John".to_owned());
+  assert!(key.is_none());
+}
+
+#[test]
+fn test_foreign_missing_field() {
+  #[derive(Deserialize)]
+  struct Document {
+    name: String,
+    author: Foreign<Account>,
+    reviewers: ForeignVec<Account>,
+  }
+
+  let document: Document = serde_json::from_str(r#"{ "name": "filename" }"#).unwrap();
+
+  assert_eq!("filename", document.name);
+  assert!(document.author.is_unloaded());
+  assert!(document.reviewers.is_empty());
+}
+
+#[test]
+fn test_foreign_from_conversions() {
+  let release: Foreign<Release> = Release {
+    name: "one".to_owned(),
+    ..Default::default()
+  }
+  .into();
+  assert!(release.is_loaded());
+
+  let release: Foreign<Release> = None.into();
+  assert!(release.is_unloaded());
+
+  let release: Foreign<Release> = Some(Release::default()).into();
+  assert!(release.is_loaded());
+
+  let author: TypedForeign<Account> = TypedKey::from_id("John").into();
+  assert_eq!("Account:John", author.key().unwrap().as_str());
+}
+
+#[test]
+fn test_model_fetch_relations() {
+  assert_eq!(&["friend"], account.fetch_relations());
+  assert!(project.fetch_relations().is_empty());
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .fetch_many(account.fetch_relations())
+    .build();
+
+  assert_eq!("SELECT * FROM Account FETCH friend", query);
+}
+
+#[test]
+fn test_foreign_missing_keys() {
+  let projects: ForeignVec<Project> = serde_json::from_str(
+    r#"["Project:one", { "id": "Project:two", "name": "two", "releases": [] }, "Project:three"]"#,
+  )
+  .unwrap();
+
+  assert_eq!(
+    vec!["Project:one", "Project:three"],
+    projects.missing_keys().collect::<Vec<_>>()
+  );
+
+  let (loaded, missing) = projects.split_loaded();
+  assert_eq!(1, loaded.len());
+  assert_eq!("two", loaded[0].name);
+  assert_eq!(2, missing.len());
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(format!(
+      "[{}]",
+      missing
+        .iter()
+        .map(|key| key.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+    ))
+    .build();
+
+  assert_eq!("SELECT * FROM [Project:one, Project:three]", query);
+}
+
+#[test]
+fn test_model_mixed_direction_traversal() {
+  assert_eq!(
+    "Account->manage->Project<-has<-Release",
+    account.out("manage").to("Project").in_("has").to("Release")
+  );
+
+  assert_eq!(
+    "->manage->Project<-manage<-Account",
+    account.managed_projects.in_("manage").to("Account")
+  );
+}
+
+mod manage {
+  use surreal_simple_querybuilder::prelude::*;
+
+  model!(Manage {
+    id,
+    pub since,
+    pub role,
+  });
+}
+
+#[test]
+fn test_relate_edge_properties() {
+  use manage::schema::model as manage;
+
+  let relation = "Account:John".with("manage").with("Project:one");
+
+  let query = QueryBuilder::new()
+    .relate(relation.as_str())
+    .set_parameterized(&[manage.since, manage.role])
+    .build();
+
+  assert_eq!(
+    "RELATE Account:John->manage->Project:one SET since = $since , role = $role",
+    query
+  );
+
+  let query = QueryBuilder::new()
+    .relate(relation.as_str())
+    .set_model(&manage)
+    .unwrap()
+    .build();
+
+  assert_eq!(
+    "RELATE Account:John->manage->Project:one SET since = $since , role = $role",
+    query
+  );
+}
+
+#[test]
+fn test_relate_nodes() {
+  use manage::schema::model as manage;
+
+  let query = QueryBuilder::new()
+    .relate_nodes("$account", manage, "$project")
+    .set_model(&manage)
+    .unwrap()
+    .param("$account", "Account:John")
+    .build();
+
+  assert_eq!(
+    "RELATE Account:John->Manage->$project SET since = $since , role = $role",
+    query
+  );
+}
+
+#[test]
+fn test_escaped_identifiers() {
+  assert_eq!("Account:⟨John Doe⟩", "John Doe".as_named_label("Account"));
+  assert_eq!("Account:⟨john:doe⟩", "Account".with_id("john:doe"));
+  assert_eq!(
+    "Account:John->manage->Project:⟨my project⟩",
+    "Account:John".with("manage").with("Project:my project")
+  );
+  assert_eq!(
+    "Project:one<-manage<-Account:⟨Jöhn⟩",
+    "Project:one".with_back("manage").with_back("Account:Jöhn")
+  );
+
+  // already escaped, parameterized or generated ids are left intact
+  assert_eq!("Account:⟨John Doe⟩", "⟨John Doe⟩".as_named_label("Account"));
+  assert_eq!("Account:`John Doe`", "`John Doe`".as_named_label("Account"));
+  assert_eq!("Account:$id", "$id".as_named_label("Account"));
+  assert_eq!("Account:rand()", "rand()".as_named_label("Account"));
+}
+
+#[test]
+fn test_complex_record_ids() {
+  let event = "event".with_array_id(&[&2024, &"us"]);
+  let task = "task".with_object_id(&[("project", &"x"), ("seq", &3), ("due date", &None::<u32>)]);
+
+  assert_eq!("event:[2024, 'us']", event);
+  assert_eq!("task:{ project: 'x', seq: 3, ⟨due date⟩: NONE }", task);
+
+  let query = QueryBuilder::new()
+    .relate(event.with("tracks").with(&task))
+    .build();
+
+  assert_eq!(
+    "RELATE event:[2024, 'us']->tracks->task:{ project: 'x', seq: 3, ⟨due date⟩: NONE }",
+    query
+  );
+}
+
+#[test]
+fn test_generated_record_ids() {
+  let query = QueryBuilder::new()
+    .create(account.with_uuid_id())
+    .set_model(&account)
+    .unwrap()
+    .build();
+
+  assert_eq!(
+    "CREATE Account:uuid() SET handle = $handle , password = $password , email = $email",
+    query
+  );
+}
+
+#[test]
+fn test_record_id() {
+  let john = RecordId::new("Account", "John Doe");
+
+  assert_eq!("Account", john.table());
+  assert_eq!("John Doe", john.id());
+  assert_eq!(john.to_string(), "John Doe".as_named_label("Account"));
+
+  let serialized = serde_json::to_string(&john).unwrap();
+  assert_eq!("\"Account:⟨John Doe⟩\"", serialized);
+  assert_eq!(john, serde_json::from_str::<RecordId>(&serialized).unwrap());
+
+  assert!("Account".parse::<RecordId>().is_err());
+  assert!(serde_json::from_str::<RecordId>("\":John\"").is_err());
+
+  let key: TypedKey<Account> = TypedKey::from_id("John");
+  assert_eq!(RecordId::new("Account", "John"), RecordId::from(key));
+}
+
+#[test]
+fn test_typed_key_escaped_id() {
+  let key: TypedKey<Account> = TypedKey::from_id("John Doe");
+  assert_eq!("Account:⟨John Doe⟩", key.as_str());
+  assert_eq!(RecordId::new("Account", "John Doe"), RecordId::from(key));
+
+  let error = TypedKey::<Account>::new("Project:one").unwrap_err();
+  assert_eq!(
+    "the key `Project:one` does not belong to the `Account` table",
+    error.to_string()
+  );
+
+  let error = TypedKey::<Account>::new("Account:").unwrap_err();
+  assert_eq!("the key `Account:` has no id", error.to_string());
+
+  let key = TypedKey::<Account>::new("Account:⟨a\\⟩b⟩").unwrap();
+  assert_eq!("a⟩b", key.id());
+  assert_eq!(key, TypedKey::from_id(&key.id()));
+}
+
+#[test]
+fn test_typed_key_model_table() {
+  use account::schema::Account as AccountModel;
+  use order::schema::Order as OrderModel;
+
+  let key = TypedKey::<AccountModel<0>>::from_id("John");
+  assert_eq!("Account:John", key.as_str());
+  assert!(TypedKey::<AccountModel<0>>::new("Project:one").is_err());
+
+  // the table prefix of the keys is the unescaped name of the table
+  let key: TypedKey<OrderModel<0>> = serde_json::from_str("\"Order:one\"").unwrap();
+  assert_eq!("one", key.id());
+}
+
+#[test]
+fn test_node_builder_model_relations() {
+  assert_eq!(
+    "Account:John->manage->Project",
+    "John"
+      .as_named_label("Account")
+      .with(account.managed_projects)
+  );
+
+  let one = &mut "one".as_named_label("Project");
+  one.with(project.authors).with("friend").with(account);
+
+  assert_eq!("Project:one<-manage<-Account->friend->Account", *one);
+}
+
+/// Poll the future until it completes, the runners used in the tests never wait
+/// on anything.
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+  let mut future = std::pin::pin!(future);
+  let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+
+  loop {
+    if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+      return output;
+    }
+  }
+}
+
+/// A runner returning the stored rows and recording the queries it receives.
+struct RecordingRunner {
+  rows: serde_json::Value,
+  queries: std::cell::RefCell<Vec<(String, Bindings)>>,
+}
+
+impl QueryRunner for RecordingRunner {
+  type Error = serde_json::Error;
+
+  async fn execute<T: serde::de::DeserializeOwned>(
+    &self, query: &str, bindings: &Bindings,
+  ) -> Result<Vec<T>, Self::Error> {
+    self
+      .queries
+      .borrow_mut()
+      .push((query.to_owned(), bindings.clone()));
+
+    serde_json::from_value(self.rows.clone())
+  }
+}
+
+#[test]
+fn test_query_runner() {
+  let runner = RecordingRunner {
+    rows: serde_json::json!([{ "name": "one" }, { "name": "two" }]),
+    queries: Default::default(),
+  };
+
+  let names: Vec<Project> = block_on(
+    QueryBuilder::new()
+      .select(project.name)
+      .from(project)
+      .filter(project.name.equals_parameterized())
+      .bind(project.name.to_string().as_str(), "one")
+      .unwrap()
+      .fetch_all(&runner),
+  )
+  .unwrap();
+
+  assert_eq!(2, names.len());
+  assert_eq!("two", names[1].name);
+
+  let first: Option<Project> = block_on(
+    QueryBuilder::new()
+      .select("*")
+      .from(project)
+      .fetch_one(&runner),
+  )
+  .unwrap();
+
+  assert_eq!(Some("one".to_owned()), first.map(|p| p.name));
+
+  let queries = runner.queries.borrow();
+  assert_eq!("SELECT name FROM Project WHERE name = $name", queries[0].0);
+  assert_eq!(Some(&serde_json::json!("one")), queries[0].1.get("name"));
+  assert_eq!("SELECT * FROM Project", queries[1].0);
+  assert!(queries[1].1.is_empty());
+}
+
+#[test]
+fn test_typed_query() {
+  let runner = RecordingRunner {
+    rows: serde_json::json!([{ "handle": "John", "password": "", "email": "" }]),
+    queries: Default::default(),
+  };
+
+  let accounts_query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .into_typed::<Account>();
+
+  let accounts = block_on(accounts_query.fetch_all(&runner)).unwrap();
+
+  assert_eq!("John", accounts[0].handle);
+  assert_eq!("SELECT * FROM Account", runner.queries.borrow()[0].0);
+}
+
+#[test]
+fn test_query_value() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.handle.equals_parameterized())
+    .bind("handle", "John")
+    .unwrap()
+    .build_query();
+
+  assert_eq!("SELECT * FROM Account WHERE handle = $handle", query.text());
+  assert_eq!(format!("{query}"), query.text());
+
+  let runner = RecordingRunner {
+    rows: serde_json::json!([]),
+    queries: Default::default(),
+  };
+
+  let rows: Vec<Account> = block_on(query.fetch_all(&runner)).unwrap();
+  assert!(rows.is_empty());
+
+  assert_eq!(
+    serde_json::json!({
+      "text": "SELECT * FROM Account WHERE handle = $handle",
+      "bindings": { "handle": "John" }
+    }),
+    serde_json::to_value(&query).unwrap()
+  );
+
+  let (text, bindings) = query.into_parts();
+  assert_eq!(runner.queries.borrow()[0], (text, bindings));
+
+  let raw: Query = "SELECT * FROM Account".into();
+  assert!(raw.bindings().is_empty());
+}
+
+#[test]
+fn test_literal() {
+  use std::collections::BTreeMap;
+
+  #[derive(Serialize)]
+  enum Visibility {
+    Public,
+    Restricted(Vec<&'static str>),
+    Scheduled { at: Datetime },
+  }
+
+  #[derive(Serialize)]
+  struct Settings {
+    visibility: Vec<Visibility>,
+    ratio: f64,
+    limits: BTreeMap<u32, bool>,
+    pair: (i8, char),
+  }
+
+  let settings = Settings {
+    visibility: vec![
+      Visibility::Public,
+      Visibility::Restricted(vec!["admin"]),
+      Visibility::Scheduled {
+        at: Datetime::new("2024-01-01T00:00:00Z"),
+      },
+    ],
+    ratio: 1.0,
+    limits: BTreeMap::from([(10, true)]),
+    pair: (-1, 'x'),
+  };
+
+  assert_eq!(
+    "{ visibility: ['Public', { Restricted: ['admin'] }, { Scheduled: { at: d'2024-01-01T00:00:00Z' } }], ratio: 1.0, limits: { 10: true }, pair: [-1, 'x'] }",
+    literal(&settings).unwrap()
+  );
+
+  let keys = BTreeMap::from([((1, 2), "tuple key")]);
+  assert!(literal(&keys).is_err());
+}
+
+#[test]
+fn test_literal_none_and_non_finite() {
+  assert_eq!("[NONE, NULL]", literal(&(None::<u32>, ())).unwrap());
+  assert_eq!("[1.5, NONE]", literal(&[Some(1.5), None]).unwrap());
+
+  assert!(literal(&f64::NAN).is_err());
+  assert!(literal(&f32::INFINITY).is_err());
+  assert!(literal(&[1.0, f64::NEG_INFINITY]).is_err());
+}
+
+#[test]
+fn test_schema_drift() {
+  assert_eq!(
+    &["handle", "password", "email", "friend"],
+    account.field_names()
+  );
+
+  let runner = RecordingRunner {
+    rows: serde_json::json!([{
+      "fields": {
+        "handle": "DEFINE FIELD handle ON Account TYPE string",
+        "password": "DEFINE FIELD password ON Account TYPE string",
+        "email": "DEFINE FIELD email ON Account TYPE string",
+        "friend": "DEFINE FIELD friend ON Account TYPE record<Account>",
+        "settings": "DEFINE FIELD settings ON Account TYPE object",
+        "settings.theme": "DEFINE FIELD settings.theme ON Account TYPE string"
+      }
+    }]),
+    queries: Default::default(),
+  };
+
+  let table = account.to_string();
+  let drift = block_on(detect_schema_drift(&runner, &table, account.field_names())).unwrap();
+
+  assert!(drift.missing_fields.is_empty());
+  assert_eq!(vec!["settings"], drift.unexpected_fields);
+  assert_eq!("INFO FOR TABLE Account", runner.queries.borrow()[0].0);
+}
+
+mod profile {
+  use super::account::schema::Account;
+  use surreal_simple_querybuilder::prelude::*;
+
+  model!(Profile {
+    id,
+    pub handle: string,
+    pub bio: option<string | null>,
+    pub owner<Account>: record<Account>,
+    pub tags,
+
+    @unique profile_handle(handle),
+    @index profile_owner(owner, handle),
+  });
+}
+
+#[test]
+fn test_registry_drift() {
+  use profile::schema::model as profile;
+
+  assert_eq!(
+    &[
+      ("handle", "string"),
+      ("bio", "option<string | null>"),
+      ("owner", "record<Account>")
+    ],
+    profile.field_types()
+  );
+  assert_eq!(
+    vec![
+      "DEFINE TABLE Profile",
+      "DEFINE FIELD handle ON TABLE Profile TYPE string",
+      "DEFINE FIELD bio ON TABLE Profile TYPE option<string | null>",
+      "DEFINE FIELD owner ON TABLE Profile TYPE record<Account>",
+      "DEFINE INDEX profile_handle ON TABLE Profile FIELDS handle UNIQUE",
+      "DEFINE INDEX profile_owner ON TABLE Profile FIELDS owner, handle",
+    ],
+    SchemaModel::define_statements(&profile)
+  );
+
+  let runner = RecordingRunner {
+    rows: serde_json::json!([{
+      "fields": {
+        "handle": "DEFINE FIELD handle ON Profile TYPE string ASSERT $value != NONE",
+        "bio": "DEFINE FIELD bio ON Profile TYPE option<string|null>",
+        "owner": "DEFINE FIELD owner ON TABLE Profile TYPE record<Project>",
+        "tags": "DEFINE FIELD tags ON Profile"
+      },
+      "indexes": {
+        "profile_handle": "DEFINE INDEX profile_handle ON Profile FIELDS handle UNIQUE",
+        "profile_owner": "DEFINE INDEX profile_owner ON Profile FIELDS owner UNIQUE",
+        "profile_tags": "DEFINE INDEX profile_tags ON Profile FIELDS tags"
+      }
+    }]),
+    queries: Default::default(),
+  };
+
+  let registry = SchemaRegistry::new().register(&profile).register(&account);
+  let drifts = block_on(detect_registry_drift(&runner, &registry)).unwrap();
+
+  assert_eq!(2, drifts.len());
+  assert_eq!(
+    SchemaDrift {
+      table: "Profile".to_owned(),
+      mismatched_types: vec![FieldTypeDrift {
+        field: "owner".to_owned(),
+        expected: "record<Account>".to_owned(),
+        found: Some("record<Project>".to_owned()),
+      }],
+      mismatched_indexes: vec!["profile_owner".to_owned()],
+      unexpected_indexes: vec!["profile_tags".to_owned()],
+      ..Default::default()
+    },
+    drifts[0]
+  );
+
+  // the Account model declares no types nor indexes, only its fields drift
+  assert_eq!("Account", drifts[1].table);
+  assert!(drifts[1].mismatched_types.is_empty());
+  assert_eq!(3, drifts[1].unexpected_indexes.len());
+
+  let queries: Vec<String> = runner
+    .queries
+    .take()
+    .into_iter()
+    .map(|(query, _)| query)
+    .collect();
+  assert_eq!(
+    vec!["INFO FOR TABLE Profile", "INFO FOR TABLE Account"],
+    queries
+  );
+}
+
+#[test]
+fn test_build_hooks_and_stats() {
+  let unbounded_selects = std::cell::Cell::new(0);
+  let forbid_unbounded_select = |query: &str, _: &Bindings| {
+    if query.starts_with("SELECT") && !query.contains(" LIMIT ") {
+      unbounded_selects.set(unbounded_selects.get() + 1);
+    }
+  };
+
+  let (query, stats) = QueryBuilder::new()
+    .on_build(forbid_unbounded_select)
+    .select("*")
+    .from(account)
+    .filter(account.handle.equals_parameterized())
+    .bind("handle", "John")
+    .unwrap()
+    .build_with_stats();
+
+  assert_eq!(1, unbounded_selects.get());
+  assert_eq!(
+    BuildStats {
+      segment_count: 6,
+      binding_count: 1,
+      output_length: query.len(),
+    },
+    stats
+  );
+
+  QueryBuilder::new()
+    .on_build(forbid_unbounded_select)
+    .select("*")
+    .from(account)
+    .limit("10")
+    .build();
+
+  assert_eq!(1, unbounded_selects.get());
+}
+
+#[test]
+fn test_query_cache() {
+  let cache = QueryCache::new();
+  let build = |field: &'static str, ids: &'static str| {
+    QueryBuilder::new()
+      .select(field)
+      .from(account)
+      .filter("id IN {{ids}}")
+      .param("{{ids}}", ids)
+      .build_cached(&cache)
+      .0
+  };
+
+  assert_eq!(
+    "SELECT handle FROM Account WHERE id IN [1]",
+    build("handle", "[1]")
+  );
+  assert_eq!(
+    "SELECT handle FROM Account WHERE id IN [1]",
+    build("handle", "[1]")
+  );
+  assert_eq!(1, cache.len());
+
+  // the parameters and segments are both part of the shape of the query
+  assert_eq!(
+    "SELECT handle FROM Account WHERE id IN [2]",
+    build("handle", "[2]")
+  );
+  assert_eq!(
+    "SELECT email FROM Account WHERE id IN [2]",
+    build("email", "[2]")
+  );
+  assert_eq!(3, cache.len());
+
+  cache.clear();
+  assert!(cache.is_empty());
+}
+
+#[test]
+fn test_query_cache_capacity() {
+  let cache = QueryCache::with_capacity(2);
+  let build = |field: &'static str| {
+    QueryBuilder::new()
+      .select(field)
+      .from(account)
+      .build_cached(&cache)
+      .0
+  };
+
+  build("handle");
+  build("email");
+  build("handle");
+  assert_eq!(2, cache.len());
+
+  // the least recently used shape is evicted to make room for the new one
+  assert_eq!("SELECT password FROM Account", build("password"));
+  assert_eq!(2, cache.len());
+  assert_eq!("SELECT handle FROM Account", build("handle"));
+  assert_eq!(2, cache.len());
+
+  let disabled = QueryCache::with_capacity(0);
+  QueryBuilder::new().select("*").build_cached(&disabled);
+  assert!(disabled.is_empty());
+}
+
+#[test]
+fn test_testing_helpers() {
+  use surreal_simple_querybuilder::testing::*;
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.email.equals_parameterized())
+    .and("handle = 'SELECT x FROM y'")
+    .order_by_asc("handle")
+    .build();
+
+  assert_has_clause(&query, "from", "Account");
+  assert_has_clause(
+    &query,
+    "WHERE",
+    "email = $email   AND handle = 'SELECT x FROM y'",
+  );
+  assert_has_clause(&query, "ORDER BY", "handle ASC");
+  assert!(queries_eq(
+    &query,
+    "SELECT *\n  FROM Account\n  WHERE email = $email AND handle = 'SELECT x FROM y'\n  ORDER BY handle ASC"
+  ));
+  assert!(!queries_eq(&query, &query.replace("x FROM", "x  FROM")));
+}
+
+#[test]
+#[should_panic(expected = "has no `WHERE handle = $handle` clause")]
+fn test_testing_helpers_missing_clause() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.email.equals_parameterized())
+    .build();
+
+  surreal_simple_querybuilder::testing::assert_has_clause(&query, "WHERE", "handle = $handle");
+}
+
+#[test]
+fn test_build_pretty() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account.filter("handle != '(SELECT x)'"))
+    .filter("id IN (SELECT VALUE in FROM manage WHERE out IN (SELECT id FROM Project))")
+    .build_pretty();
+
+  assert_eq!(
+    "SELECT *
+FROM (Account WHERE handle != '(SELECT x)')
+WHERE id IN (
+  SELECT VALUE in
+  FROM manage
+  WHERE out IN (
+    SELECT id
+    FROM Project
+  )
+)",
+    query
+  );
+}
+
+#[test]
+fn test_paginate() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .order_by_asc(account.handle)
+    .paginate(1, 25)
+    .unwrap()
+    .build();
+
+  assert_eq!(
+    "SELECT * FROM Account ORDER BY handle ASC LIMIT 25 START AT 0",
+    query
+  );
+}
+
+#[test]
+fn test_paginate_zero() {
+  let empty_page = QueryBuilder::new().from(account).paginate(1, 0);
+  assert!(matches!(
+    empty_page,
+    Err(QueryBuilderError::InvalidPage {
+      page: 1,
+      per_page: 0
+    })
+  ));
+
+  let zero_page = QueryBuilder::new()
+    .from(account)
+    .paginate(0, 10)
+    .err()
+    .unwrap();
+  assert_eq!(
+    "there is no page 0 of 10 rows, pages are numbered from 1 and hold at least one row",
+    zero_page.to_string()
+  );
+
+  assert!(SearchQuery::new(account).paginate(0, 0).is_err());
+  assert!(Pagination::new(0, 5).is_err());
+}
+
+#[test]
+fn test_cursor_pagination() {
+  #[derive(Serialize)]
+  struct Row {
+    handle: String,
+  }
+
+  let rows = vec![
+    Row {
+      handle: "John".to_owned(),
+    },
+    Row {
+      handle: "Mark".to_owned(),
+    },
+  ];
+
+  let cursor = next_cursor(&rows, &account.handle.to_string()).unwrap();
+  assert_eq!(Some(serde_json::json!("Mark")), cursor);
+
+  let (query, bindings) = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .after(account.handle, cursor, Direction::Desc)
+    .unwrap()
+    .limit(2)
+    .build_with_bindings();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE handle < $cursor ORDER BY handle DESC LIMIT 2",
+    query
+  );
+  assert_eq!(Some(&serde_json::json!("Mark")), bindings.get("cursor"));
+}
+
+#[test]
+fn test_cursor_pagination_with_filter() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("email != NONE")
+    .after(account.handle, "Mark", Direction::Asc)
+    .unwrap()
+    .build();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE (email != NONE) AND (handle > $cursor) ORDER BY handle ASC",
+    query
+  );
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("a = 1 OR b = 2")
+    .order_by_desc("email")
+    .limit(10)
+    .after("created", 12, Direction::Asc)
+    .unwrap()
+    .after(account.handle, "Mark", Direction::Desc)
+    .unwrap()
+    .try_build_with(&BuildOptions::default())
+    .unwrap();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE (a = 1 OR b = 2) AND (created > $cursor) AND (handle < $cursor_2) \
+     ORDER BY handle DESC , created ASC , email DESC LIMIT 10",
+    query.text()
+  );
+  assert_eq!(Some(&serde_json::json!(12)), query.bindings().get("cursor"));
+  assert_eq!(
+    Some(&serde_json::json!("Mark")),
+    query.bindings().get("cursor_2")
+  );
+}
+
+impl SurrealCrud<Account> for account::schema::Account<0> {}
+
+#[test]
+fn test_crud_update() {
+  #[derive(Serialize)]
+  struct EmailPatch {
+    email: String,
+  }
+
+  let runner = RecordingRunner {
+    rows: serde_json::json!([{ "handle": "John", "password": "", "email": "john@mail.com" }]),
+    queries: Default::default(),
+  };
+
+  let patch = EmailPatch {
+    email: "john@mail.com".to_owned(),
+  };
+
+  let updated = block_on(
+    account
+      .update("John Doe", &patch)
+      .unwrap()
+      .fetch_one(&runner),
+  )
+  .unwrap();
+
+  assert_eq!("john@mail.com", updated.unwrap().email);
+
+  let queries = runner.queries.borrow();
+  assert_eq!("UPDATE Account:⟨John Doe⟩ MERGE $patch", queries[0].0);
+  assert_eq!(
+    Some(&serde_json::json!({ "email": "john@mail.com" })),
+    queries[0].1.get("patch")
+  );
+}
+
+#[test]
+fn test_query_macro() {
+  let (query, bindings) = query!(
+    "UPDATE {} SET {} WHERE {}",
+    account,
+    account.email.equals_parameterized(),
+    account.handle.equals_parameterized();
+    email = "john@mail.com",
+    handle = "John",
+  )
+  .unwrap()
+  .build_with_bindings();
+
+  assert_eq!(
+    "UPDATE Account SET email = $email WHERE handle = $handle",
+    query
+  );
+  assert_eq!(2, bindings.len());
+}
+
+#[test]
+fn test_count_where() {
+  let runner = RecordingRunner {
+    rows: serde_json::json!([{ "count": 3 }]),
+    queries: Default::default(),
+  };
+
+  let count = block_on(
+    account
+      .count_where(account.handle.equals_parameterized())
+      .bind("handle", "John")
+      .unwrap()
+      .fetch_count(&runner),
+  )
+  .unwrap();
+
+  assert_eq!(3, count);
+  assert_eq!(
+    "SELECT count() FROM Account WHERE handle = $handle GROUP ALL",
+    runner.queries.borrow()[0].0
+  );
+
+  let empty = RecordingRunner {
+    rows: serde_json::json!([]),
+    queries: Default::default(),
+  };
+
+  assert_eq!(
+    0,
+    block_on(QueryBuilder::new().count_from(account).fetch_count(&empty)).unwrap()
+  );
+}
+
+#[test]
+fn test_exists_where() {
+  let runner = RecordingRunner {
+    rows: serde_json::json!([true]),
+    queries: Default::default(),
+  };
+
+  let taken = block_on(
+    account
+      .exists_where(account.handle.equals_parameterized())
+      .bind("handle", "John")
+      .unwrap()
+      .fetch_exists(&runner),
+  )
+  .unwrap();
+
+  assert!(taken);
+  assert_eq!(
+    "SELECT VALUE true FROM Account WHERE handle = $handle LIMIT 1",
+    runner.queries.borrow()[0].0
+  );
+}
+
+#[test]
+fn test_insert_many() {
+  let releases = vec![
+    Release {
+      id: None,
+      name: "v1".to_owned(),
+    },
+    Release {
+      id: Some("Release:two".to_owned()),
+      name: "v2".to_owned(),
+    },
+  ];
+
+  let query = QueryBuilder::new()
+    .insert_many("Release", &releases)
+    .unwrap()
+    .build();
+
+  assert_eq!(
+    "INSERT INTO Release [{ id: NONE, name: 'v1' }, { id: 'Release:two', name: 'v2' }]",
+    query
+  );
+
+  let empty = QueryBuilder::new()
+    .insert_many("Release", Vec::<Release>::new())
+    .unwrap()
+    .build();
+
+  assert_eq!("", empty);
+}
+
+#[test]
+fn test_insert_many_chunked_escaped_table() {
+  let query = QueryBuilder::new()
+    .insert_many_chunked("Order", [1, 2, 3], 2)
+    .unwrap()
+    .build();
+
+  assert_eq!(
+    "INSERT INTO ⟨Order⟩ [1, 2] ; INSERT INTO ⟨Order⟩ [3]",
+    query
+  );
+
+  let spaced = QueryBuilder::new()
+    .insert_many("Sales Order", [1])
+    .unwrap()
+    .build();
+
+  assert_eq!("INSERT INTO ⟨Sales Order⟩ [1]", spaced);
+}
+
+#[test]
+fn test_crud_find_one() {
+  let runner = RecordingRunner {
+    rows: serde_json::json!([{ "handle": "John", "password": "", "email": "" }]),
+    queries: Default::default(),
+  };
+
+  let found = block_on(
+    account
+      .find_one(account.email.equals_parameterized())
+      .into_inner()
+      .bind("email", "john@mail.com")
+      .unwrap()
+      .into_typed::<Account>()
+      .fetch_one(&runner),
+  )
+  .unwrap();
+
+  assert_eq!("John", found.unwrap().handle);
+  assert_eq!(
+    "SELECT * FROM Account WHERE email = $email LIMIT 1",
+    runner.queries.borrow()[0].0
+  );
+}
+
+#[test]
+fn test_search_query() {
+  let query = SearchQuery::new(project)
+    .matches(project.name, "$terms")
+    .filter(project.name.not_equals("'draft'"))
+    .build()
+    .build();
+
+  assert_eq!(
+    "SELECT * FROM Project WHERE (name @1@ $terms) AND (name != 'draft')",
+    query
+  );
+}
+
+#[test]
+fn test_search_query_or_filter() {
+  let query = SearchQuery::new(project)
+    .matches(project.name, "$terms")
+    .filter("public = true OR owner = $auth.id")
+    .filter("archived = false")
+    .build()
+    .build();
+
+  assert_eq!(
+    "SELECT * FROM Project WHERE (name @1@ $terms) AND (public = true OR owner = $auth.id) AND (archived = false)",
+    query
+  );
+}
+
+#[test]
+fn test_aggregate_group_all() {
+  let aggregate = Aggregate::new(account).aggregate("accounts", "count()");
+
+  assert_eq!(vec!["accounts"], aggregate.result_fields());
+  assert_eq!(
+    "SELECT count() AS accounts FROM Account GROUP ALL",
+    aggregate.build().build()
+  );
+}
+
+#[test]
+fn test_aggregate_or_filter() {
+  let aggregate = Aggregate::new("Order")
+    .aggregate("orders", "count()")
+    .filter("status = 'paid' OR status = 'refunded'")
+    .filter("total > 0");
+
+  assert_eq!(
+    "SELECT count() AS orders FROM Order WHERE (status = 'paid' OR status = 'refunded') AND (total > 0) GROUP ALL",
+    aggregate.build().build()
+  );
+}
+
+#[test]
+fn test_tree_traversal() {
+  let depth = "2".parse().unwrap();
+
+  let query = QueryBuilder::new()
+    .select(
+      "Release:one"
+        .ancestors("has", depth)
+        .as_alias("grand_parents"),
+    )
+    .from("Release:one")
+    .build();
+
+  assert_eq!(
+    "SELECT Release:one->has->?->has->? AS grand_parents FROM Release:one",
+    query
+  );
+  assert_eq!("Release:one", "Release:one".descendants("has", 0));
+}
+
+#[test]
+fn test_edges_between() {
+  let query = QueryBuilder::new()
+    .edges_between("Account:⟨John Doe⟩", "manage", "Project:one")
+    .build();
+
+  assert_eq!(
+    "SELECT * FROM manage WHERE (in = Account:⟨John Doe⟩ AND out = Project:one) OR (in = Project:one AND out = Account:⟨John Doe⟩)",
+    query
+  );
+}
+
+#[test]
+fn test_try_build_clauses() {
+  let create = QueryBuilder::new()
+    .create(account)
+    .set(account.handle.equals_parameterized())
+    .limit("1")
+    .try_build();
+
+  assert_eq!(
+    Err(QueryBuilderError::UnexpectedClause {
+      statement: "CREATE",
+      clause: "LIMIT"
+    }),
+    create
+  );
+
+  let select = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .set("handle = 'John'")
+    .try_build();
+
+  assert_eq!(
+    Err(QueryBuilderError::UnexpectedClause {
+      statement: "SELECT",
+      clause: "SET"
+    }),
+    select
+  );
+
+  let chunks = QueryBuilder::new()
+    .insert_many_chunked("Release", [1, 2, 3], 2)
+    .unwrap()
+    .raw(";")
+    .raw("SELECT * FROM Release WHERE id IN (SELECT VALUE out FROM has LIMIT 1) FETCH has")
+    .try_build();
+
+  assert!(chunks.is_ok());
+}
+
+#[test]
+fn test_try_build_keyword_named_fields() {
+  let select = QueryBuilder::new()
+    .select("content, set, limit")
+    .from("Post")
+    .filter("start > 10")
+    .and("order = 1 OR group = 2")
+    .order_by_asc("start")
+    .try_build();
+
+  assert_eq!(
+    Ok(
+      "SELECT content, set, limit FROM Post WHERE start > 10 AND order = 1 OR group = 2 ORDER BY start ASC"
+        .to_owned()
+    ),
+    select
+  );
+
+  let escaped = QueryBuilder::new()
+    .select("⟨LIMIT⟩, `SET`")
+    .from("Post")
+    .try_build();
+
+  assert!(escaped.is_ok());
+
+  let query = "SELECT content FROM Post WHERE start > 10 ORDER BY start ASC";
+  assert_eq!(
+    vec![
+      ("SELECT".to_owned(), "content".to_owned()),
+      ("FROM".to_owned(), "Post".to_owned()),
+      ("WHERE".to_owned(), "start > 10".to_owned()),
+      ("ORDER BY".to_owned(), "start ASC".to_owned()),
+    ],
+    surreal_simple_querybuilder::testing::clauses(query)
+  );
+}
+
+#[test]
+fn test_try_build_unbound_parameters() {
+  let options = BuildOptions {
+    deny_unbound_parameters: true,
+    ..Default::default()
+  };
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.handle.equals_parameterized())
+    .and("email = '$not_a_parameter'")
+    .bind("handle", "John")
+    .unwrap()
+    .try_build_with(&options)
+    .unwrap();
+
+  assert_eq!(1, query.bindings().len());
+
+  let missing = QueryBuilder::new()
+    .update(account)
+    .set(account.email.equals_parameterized())
+    .filter(account.handle.equals_parameterized())
+    .and("$handle != NONE")
+    .try_build_with(&options);
+
+  assert_eq!(
+    Err(QueryBuilderError::UnboundParameters(vec![
+      "$email".to_owned(),
+      "$handle".to_owned()
+    ])),
+    missing.map(Query::into_parts)
+  );
+}
+
+#[test]
+fn test_param_single_pass() {
+  let query = QueryBuilder::new()
+    .select("{{fields}}")
+    .from("{{table}}")
+    .param("{{fields}}", "{{fields}}, {{table}}")
+    .param("{{table}}", "Account")
+    .param("{{table}}s", "Accounts")
+    .build();
+
+  assert_eq!("SELECT {{fields}}, {{table}} FROM Account", query);
+
+  let longest = QueryBuilder::new()
+    .from("{{table}}s")
+    .param("{{table}}", "Account")
+    .param("{{table}}s", "Accounts")
+    .build();
+
+  assert_eq!("FROM Accounts", longest);
+}
+
+#[test]
+fn test_escape_str_literals() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.handle.in_list(&["it's", "line\nbreak"]))
+    .build();
+
+  assert_eq!(
+    r"SELECT * FROM Account WHERE handle IN ['it\'s', 'line\nbreak']",
+    query
+  );
+}
+
+#[test]
+fn test_in_list_literals() {
+  let authors = [
+    RecordId::new("Account", "john"),
+    RecordId::new("Account", "John Doe"),
+  ];
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from("Post")
+    .filter("author".in_list(&authors))
+    .and("score".not_in_list(&[1.5, 2.0]))
+    .and("id".in_list(&[&1 as &dyn SqlLiteral, &"one"]))
+    .build();
+
+  assert_eq!(
+    "SELECT * FROM Post WHERE author IN [Account:john, Account:⟨John Doe⟩] AND score NOT IN [1.5, 2.0] AND id IN [1, 'one']",
+    query
+  );
+}
+
+#[test]
+fn test_try_build_raw_text() {
+  let options = BuildOptions {
+    deny_raw_text: true,
+    ..Default::default()
+  };
+
+  let bound = QueryBuilder::new()
+    .count_from(account)
+    .try_build_with(&options);
+
+  assert!(bound.is_ok());
+
+  let raw = QueryBuilder::new()
+    .select("{{fields}}")
+    .from(account)
+    .commas(|query| query.raw("a").raw("b"))
+    .param("{{fields}}", "handle")
+    .try_build_with(&options)
+    .unwrap_err();
+
+  assert_eq!(QueryBuilderError::RawText(vec!["raw", "param"]), raw);
+  assert_eq!(
+    "the query holds raw text inserted by raw(), param()",
+    raw.to_string()
+  );
+}
+
+#[test]
+fn test_try_build_raw_text_query_macro() {
+  let options = BuildOptions {
+    deny_raw_text: true,
+    ..Default::default()
+  };
+
+  let table = "Account";
+  let templated = surreal_simple_querybuilder::query!("SELECT * FROM {table}")
+    .try_build_with(&options)
+    .unwrap_err();
+
+  assert_eq!(QueryBuilderError::RawText(vec!["query!"]), templated);
+  assert_eq!(
+    "the query holds raw text inserted by query!()",
+    templated.to_string()
+  );
+}
+
+#[test]
+fn test_try_build_duplicate_clauses() {
+  let options = BuildOptions {
+    deny_duplicate_clauses: true,
+    ..Default::default()
+  };
+
+  let error = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.handle.equals_parameterized())
+    .and_where(account.email.equals_parameterized())
+    .try_build_with(&options)
+    .unwrap_err();
+
+  assert_eq!(
+    "a SELECT statement cannot have more than one WHERE clause",
+    error.to_string()
+  );
+
+  let valid = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("id IN (SELECT VALUE in FROM manage WHERE out = $project)")
+    .and(account.handle.equals_parameterized())
+    .try_build_with(&options);
+
+  assert!(valid.is_ok());
+}
+
+#[test]
+fn test_commas_keeps_bindings() {
+  let (query, bindings) = QueryBuilder::new()
+    .update(account)
+    .set("")
+    .commas(|query| {
+      query
+        .raw("handle = $handle")
+        .raw("{{email}}")
+        .param("{{email}}", "email = $email")
+        .bind("handle", "John")
+        .unwrap()
+        .bind("email", "john@mail.com")
+        .unwrap()
+    })
+    .build_with_bindings();
+
+  assert_eq!(
+    "UPDATE Account SET handle = $handle , email = $email",
+    query
+  );
+  assert_eq!(2, bindings.len());
+}
+
+#[test]
+fn test_many_with_empty_fields() {
+  let fields: Vec<&str> = Vec::new();
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .fetch_many(&fields)
+    .build();
+
+  assert_eq!("SELECT * FROM Account", query);
+
+  let update = QueryBuilder::new()
+    .update(account)
+    .set_many(&fields)
+    .order_by_desc_many(&fields)
+    .build();
+
+  assert_eq!("UPDATE Account", update);
+}
+
+#[test]
+fn test_try_build_compact() {
+  let options = BuildOptions {
+    compact: true,
+    ..Default::default()
+  };
+
+  let query = QueryBuilder::new()
+    .select_many(&["handle", "email"])
+    .from(account)
+    .filter("id IN ( SELECT VALUE in FROM manage WHERE name = 'two  spaces' )")
+    .and("tags CONTAINSANY [ 'a' , 'b' ]")
+    .try_build_with(&options)
+    .unwrap();
+
+  assert_eq!(
+    "SELECT handle,email FROM Account WHERE id IN (SELECT VALUE in FROM manage WHERE name = 'two  spaces') AND tags CONTAINSANY ['a','b']",
+    query.into_parts().0
+  );
+}
+
+#[test]
+fn test_try_build_size_limits() {
+  let query = || {
+    QueryBuilder::new()
+      .select("*")
+      .from(account)
+      .filter("handle IN [")
+      .commas(|query| query.raw("'John'").raw("'Mark'").raw("'Paul'"))
+      .raw("]")
+  };
+
+  let segments = BuildOptions {
+    max_segment_count: Some(10),
+    ..Default::default()
+  };
+
+  assert_eq!(
+    Err(QueryBuilderError::TooManySegments {
+      count: 12,
+      limit: 10
+    }),
+    query().try_build_with(&segments).map(|_| ())
+  );
+
+  let length = BuildOptions {
+    max_output_length: Some(32),
+    ..Default::default()
+  };
+
+  let error = query().try_build_with(&length).unwrap_err();
+  assert_eq!(
+    "the query is 66 bytes long, more than the limit of 32",
+    error.to_string()
+  );
+
+  let within = BuildOptions {
+    max_output_length: Some(66),
+    max_segment_count: Some(12),
+    ..Default::default()
+  };
+
+  assert!(query().try_build_with(&within).is_ok());
+}
+
+mod order {
+  use super::account::schema::Account;
+  use surreal_simple_querybuilder::prelude::*;
+
+  model!(Order {
+    id,
+    pub value,
+    pub prénom,
+    pub buyer<Account>,
+  });
+}
+
+#[test]
+fn test_reserved_field_names() {
+  use order::schema::model as order;
+
+  let query = QueryBuilder::new()
+    .select(order.value)
+    .from(order)
+    .filter(order.prénom.equals_parameterized())
+    .and(order.buyer().handle.equals_parameterized())
+    .build();
+
+  assert_eq!(
+    "SELECT ⟨value⟩ FROM ⟨Order⟩ WHERE ⟨prénom⟩ = $prénom AND buyer.handle = $buyer_handle",
+    query
+  );
+
+  let update = QueryBuilder::new()
+    .update(order)
+    .set_model(&order)
+    .unwrap()
+    .build();
+
+  assert_eq!(
+    "UPDATE ⟨Order⟩ SET ⟨value⟩ = $value , ⟨prénom⟩ = $prénom , buyer = $buyer",
+    update
+  );
+
+  assert_eq!("value", order.value.name());
+}
+
+#[test]
+fn test_crud_reserved_table() {
+  use order::schema::model as order;
+
+  #[derive(Serialize, Deserialize)]
+  struct Order {
+    id: Option<String>,
+    value: u32,
+  }
+
+  impl SurrealCrud<Order> for order::schema::Order<0> {}
+
+  assert_eq!("Order", order.table_name());
+  assert_eq!("SELECT * FROM ⟨Order⟩:x", order.find("Order:x").build());
+  assert_eq!("SELECT * FROM ⟨Order⟩:x", order.find("x").build());
+  assert_eq!("DELETE ⟨Order⟩:⟨a b⟩", order.delete("Order:a b").build());
+  assert_eq!(
+    "SELECT * FROM ⟨Order⟩ WHERE ⟨value⟩ > 1",
+    order.find_where("⟨value⟩ > 1").build()
+  );
+
+  let order_row = Order {
+    id: Some("Order:x".to_owned()),
+    value: 1,
+  };
+  assert_eq!(
+    "UPSERT ⟨Order⟩:x CONTENT $content RETURN AFTER",
+    order.save(&order_row).unwrap().build()
+  );
+
+  let bindings = order.find_by_id("x").unwrap().build_with_bindings().1;
+  assert_eq!(Some(&serde_json::json!("Order")), bindings.get("tb"));
+}
+
+#[test]
+fn test_from_ops() {
+  let ops = [
+    Op::Update("Account"),
+    Op::Set("email = $email"),
+    Op::Filter("handle = $handle"),
+    Op::AndGroup("friend = $friend"),
+    Op::Or("friend IS NONE"),
+    Op::EndGroup,
+    Op::EndGroup,
+    Op::Param("$email", "'john@mail.com'"),
+  ];
+
+  let query = QueryBuilder::new()
+    .update("Account")
+    .set("email = $email")
+    .filter("handle = $handle")
+    .and_group("friend = $friend", |query| query.or("friend IS NONE"))
+    .param("$email", "'john@mail.com'");
+
+  assert_eq!(query.build(), QueryBuilder::from_ops(&ops).build());
+
+  let unclosed = QueryBuilder::from_ops(&[
+    Op::Select("*"),
+    Op::From("Account"),
+    Op::Filter("true"),
+    Op::AndGroup("a"),
+    Op::AndGroup("b"),
+  ]);
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE true AND ( a AND ( b ) )",
+    unclosed.build()
+  );
+}
+
+#[test]
+fn test_from_ops_group_placement() {
+  let limited = QueryBuilder::from_ops(&[
+    Op::Select("*"),
+    Op::From("Account"),
+    Op::Filter("true"),
+    Op::AndGroup("a"),
+    Op::Or("b"),
+    Op::OrderByAsc("handle"),
+    Op::EndGroup,
+    Op::Limit(10),
+  ]);
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE true AND ( a OR b ) ORDER BY handle ASC LIMIT 10",
+    limited.build()
+  );
+
+  let leading = QueryBuilder::from_ops(&[
+    Op::Select("*"),
+    Op::From("Account"),
+    Op::AndGroup("a"),
+    Op::Or("b"),
+  ]);
+
+  assert_eq!("SELECT * FROM Account WHERE ( a OR b )", leading.build());
+}
+
+#[test]
+#[should_panic(expected = "dropped the opening parenthesis")]
+#[cfg(debug_assertions)]
+fn test_and_group_invariant() {
+  QueryBuilder::new()
+    .filter("true")
+    .and_group("a", |_| QueryBuilder::new())
+    .build();
+}
+
+#[test]
+fn test_try_build_malformed_traversal() {
+  let valid = QueryBuilder::new()
+    .select(account.managed_projects)
+    .also("<-manage<-(Account WHERE handle = 'a->')")
+    .also("->knows->?")
+    .from(account)
+    .try_build();
+
+  assert!(valid.is_ok());
+
+  let dangling = QueryBuilder::new()
+    .select("->manage->")
+    .from(account)
+    .try_build()
+    .unwrap_err();
+
+  assert_eq!(
+    QueryBuilderError::MalformedTraversal("->manage->".to_owned()),
+    dangling
+  );
+
+  let doubled = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("->manage<-->Project CONTAINS $project")
+    .try_build()
+    .unwrap_err();
+
+  assert_eq!(
+    "the traversal ->manage<-->Project has an arrow leading nowhere",
+    doubled.to_string()
+  );
+}
+
+#[test]
+fn test_owned_segments() {
+  let field = "email";
+  let query = QueryBuilder::new()
+    .select(format!("{field}, {{{{extra}}}}"))
+    .from(account)
+    .filter(format!("{field} = ${field}"))
+    .raw(format!("LIMIT {}", 5))
+    .param("{{extra}}".to_owned(), format!("string::len({field})"))
+    .build();
+
+  assert_eq!(
+    "SELECT email, string::len(email) FROM Account WHERE email = $email LIMIT 5",
+    query
+  );
+}
+
+#[test]
+fn test_extend() {
+  let scope = QueryBuilder::new()
+    .and("tenant = $tenant")
+    .bind("tenant", "acme")
+    .unwrap()
+    .param("{{deleted}}", "deleted_at IS NONE")
+    .and("{{deleted}}");
+
+  let (query, bindings) = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.handle.equals_parameterized())
+    .bind("handle", "John")
+    .unwrap()
+    .extend(scope)
+    .build_with_bindings();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE handle = $handle AND tenant = $tenant AND deleted_at IS NONE",
+    query
+  );
+  assert_eq!(2, bindings.len());
+}
+
+#[test]
+fn test_select_model() {
+  let query = QueryBuilder::new()
+    .select_model(&account)
+    .filter(account.handle.equals_parameterized())
+    .build();
+
+  assert_eq!(
+    "SELECT handle , password , email , friend FROM Account WHERE handle = $handle",
+    query
+  );
+
+  use order::schema::model as order;
+  let query = QueryBuilder::new().select_model(&order).build();
+
+  assert_eq!("SELECT id , ⟨value⟩ , ⟨prénom⟩ , buyer FROM ⟨Order⟩", query);
+}
+
+#[test]
+fn test_filter_object() {
+  let mut filters = std::collections::HashMap::new();
+  filters.insert("value", serde_json::Value::from(10));
+  filters.insert("handle", serde_json::Value::from("John"));
+  filters.insert("email", serde_json::Value::Null);
+
+  let (query, bindings) = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter_object(&filters)
+    .unwrap()
+    .build_with_bindings();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE (handle = $handle) AND (⟨value⟩ = $value)",
+    query
+  );
+  assert_eq!(Some(&serde_json::Value::from(10)), bindings.get("value"));
+
+  let empty = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter_object(&std::collections::HashMap::<String, String>::new())
+    .unwrap()
+    .build();
+
+  assert_eq!("SELECT * FROM Account", empty);
+  assert!(QueryBuilder::new().filter_object(&"handle").is_err());
+}
+
+#[test]
+fn test_filter_object_after_filter() {
+  let mut filters = std::collections::HashMap::new();
+  filters.insert("handle", "John");
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("email != NONE")
+    .filter_object(&filters)
+    .unwrap()
+    .build();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE (email != NONE) AND (handle = $handle)",
+    query
+  );
+
+  #[derive(Serialize)]
+  struct Search {
+    c: u32,
+    d: u32,
+  }
+
+  let (query, bindings) = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("a = 1 OR b = 2")
+    .limit(5)
+    .filter_object(&Search { c: 1, d: 2 })
+    .unwrap()
+    .build_with_bindings();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE (a = 1 OR b = 2) AND (c = $c) AND (d = $d) LIMIT 5",
+    query
+  );
+  assert_eq!(2, bindings.len());
+}
+
+#[test]
+fn test_set_object_values() {
+  #[derive(Serialize)]
+  struct Patch {
+    handle: String,
+    friend: Option<String>,
+
+    #[serde(skip_serializing)]
+    #[allow(dead_code)]
+    password: String,
+  }
+
+  let patch = Patch {
+    handle: "John".to_owned(),
+    friend: None,
+    password: "secret".to_owned(),
+  };
+
+  let (query, bindings) = QueryBuilder::new()
+    .update("Account:John")
+    .set_object_values(&patch)
+    .unwrap()
+    .build_with_bindings();
+
+  assert_eq!(
+    "UPDATE Account:John SET handle = $handle , friend = $friend",
+    query
+  );
+  assert_eq!(Some(&serde_json::Value::Null), bindings.get("friend"));
+  assert_eq!(None, bindings.get("password"));
+  assert!(QueryBuilder::new().set_object_values(&42).is_err());
+}
+
+#[test]
+fn test_if_let() {
+  let search = |handle: Option<&str>| {
+    QueryBuilder::new()
+      .select("*")
+      .from(account)
+      .if_let(handle, |query, handle| {
+        query
+          .filter(account.handle.equals_parameterized())
+          .bind("handle", handle)
+          .unwrap()
+      })
+      .build_with_bindings()
+  };
+
+  let (query, bindings) = search(Some("John"));
+  assert_eq!("SELECT * FROM Account WHERE handle = $handle", query);
+  assert_eq!(
+    Some(&serde_json::Value::from("John")),
+    bindings.get("handle")
+  );
+
+  let (query, bindings) = search(None);
+  assert_eq!("SELECT * FROM Account", query);
+  assert!(bindings.is_empty());
+}
+
+#[test]
+fn test_for_each_and_extend_segments() {
+  let updates = vec![(account.handle, "John"), (account.email, "john@mail.com")];
+
+  let (query, bindings) = QueryBuilder::new()
+    .update("Account:John")
+    .set("updated_at = time::now()")
+    .for_each(updates, |query, (field, value)| {
+      query
+        .also(field.equals_parameterized())
+        .bind(field.name(), value)
+        .unwrap()
+    })
+    .build_with_bindings();
+
+  assert_eq!(
+    "UPDATE Account:John SET updated_at = time::now() , handle = $handle , email = $email",
+    query
+  );
+  assert_eq!(2, bindings.len());
+
+  let options = BuildOptions {
+    deny_raw_text: true,
+    ..Default::default()
+  };
+
+  let nothing: [&str; 0] = [];
+  let untracked = QueryBuilder::new()
+    .count_from(account)
+    .extend_segments(nothing)
+    .try_build_with(&options);
+  assert!(untracked.is_ok());
+
+  let raw = QueryBuilder::new()
+    .extend_segments(vec!["SELECT * FROM Account".to_owned()])
+    .try_build_with(&options);
+  assert_eq!(
+    Err(QueryBuilderError::RawText(vec!["extend_segments"])),
+    raw.map(|_| ())
+  );
+}
+
+#[test]
+fn test_checkpoint_rollback() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.handle.equals_parameterized())
+    .bind("handle", "John")
+    .unwrap();
+
+  let checkpoint = query.checkpoint();
+  let query = query
+    .and("{{condition}}")
+    .param("{{condition}}", "email = $email")
+    .bind("email", "john@mail.com")
+    .unwrap()
+    .bind("handle", "Mark")
+    .unwrap()
+    .rollback_to(checkpoint);
+
+  let options = BuildOptions {
+    deny_raw_text: true,
+    deny_unbound_parameters: true,
+    ..Default::default()
+  };
+
+  let (text, bindings) = query.try_build_with(&options).unwrap().into_parts();
+  assert_eq!("SELECT * FROM Account WHERE handle = $handle", text);
+  assert_eq!(
+    Some(&serde_json::Value::from("John")),
+    bindings.get("handle")
+  );
+  assert_eq!(None, bindings.get("email"));
+}
+
+#[test]
+fn test_checkpoint_nested_rollback() {
+  let query = QueryBuilder::new()
+    .select("{{fields}}")
+    .from(account)
+    .param("{{fields}}", "handle");
+
+  let outer = query.checkpoint();
+  let query = query.param("{{fields}}", "email").bind("limit", 1).unwrap();
+
+  let inner = query.checkpoint();
+  let query = query
+    .param("{{fields}}", "password")
+    .bind("limit", 2)
+    .unwrap()
+    .rollback_to(inner);
+
+  assert_eq!(Some(&serde_json::json!(1)), query.bindings().get("limit"));
+
+  let (text, bindings) = query.rollback_to(outer).build_with_bindings();
+  assert_eq!("SELECT handle FROM Account", text);
+  assert!(bindings.is_empty());
+}
+
+#[test]
+fn test_query_fragments() {
+  let filters = vec![
+    FieldFilter::new("handle", "John"),
+    FieldFilter::new("settings.theme", "dark"),
+  ];
+
+  let (query, bindings) = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("verified = true")
+    .apply((filters, None::<OrderBy>, Pagination::new(1, 20).unwrap()))
+    .raw(";")
+    .select("*")
+    .from(project)
+    .apply(FieldFilter::new("name", "surreal"))
+    .build_with_bindings();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE (verified = true) AND (handle = $handle) AND (settings.theme = $settings_theme) LIMIT 20 START AT 0 ; SELECT * FROM Project WHERE name = $name",
+    query
+  );
+  assert_eq!(3, bindings.len());
+}
+
+#[test]
+fn test_query_fragments_or_filter() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("a = 1 OR b = 2")
+    .apply(FieldFilter::new("c", 3))
+    .build();
+  assert_eq!(
+    "SELECT * FROM Account WHERE (a = 1 OR b = 2) AND (c = $c)",
+    query
+  );
+
+  // the condition is written before the clauses following the WHERE clause
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .apply(Pagination::new(2, 10).unwrap())
+    .apply(FieldFilter::new("c", 3))
+    .apply(FieldFilter::new("d", 4))
+    .build();
+  assert_eq!(
+    "SELECT * FROM Account WHERE (c = $c) AND (d = $d) LIMIT 10 START AT 10",
+    query
+  );
+
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .apply(Pagination::new(2, 10).unwrap())
+    .apply(FieldFilter::new("c", 3));
+  let checkpoint = query.checkpoint();
+  let query = query.apply(FieldFilter::new("d", 4)).fetch("projects");
+
+  let (query, bindings) = query.rollback_to(checkpoint).build_with_bindings();
+  assert_eq!(
+    "SELECT * FROM Account WHERE c = $c LIMIT 10 START AT 10",
+    query
+  );
+  assert_eq!(1, bindings.len());
+}
+
+#[test]
+fn test_into_query() {
+  let text: String = QueryBuilder::new().select("*").from(account).into();
+  assert_eq!("SELECT * FROM Account", text);
+
+  let queries: Vec<Query> = vec![
+    text.clone().into_query(),
+    text.as_str().into_query(),
+    QueryBuilder::new().select("*").from(account).into_query(),
+    Query::new(text.clone(), Bindings::new()).into_query(),
+  ];
+
+  assert!(queries.iter().all(|query| query.text() == text));
+}
+
+#[test]
+fn test_ops_round_trip() {
+  let ops = [
+    Op::Select("*"),
+    Op::From("Account"),
+    Op::Filter(r#"bio CONTAINS "rust" AND handle != 'it\'s'"#),
+    Op::AndGroup("friend = $friend"),
+    Op::Or("friend IS NONE"),
+    Op::EndGroup,
+    Op::Param("$friend", "Account:John"),
+  ];
+
+  let json = serde_json::to_string(&ops).unwrap();
+  let replayed: Vec<Op<String>> = serde_json::from_str(&json).unwrap();
+
+  assert_eq!(ops.len(), replayed.len());
+  assert_eq!(
+    Op::Param("$friend".to_owned(), "Account:John".to_owned()),
+    replayed[6]
+  );
+  assert_eq!(
+    QueryBuilder::from_ops(&ops).build(),
+    QueryBuilder::from_ops(&replayed).build()
+  );
+}
+
+#[test]
+fn test_chunked_in() {
+  let ids: Vec<u32> = (0..5).collect();
+
+  let (query, bindings) = QueryBuilder::new()
+    .chunked_in("id", &ids, 2, |query, condition| {
+      query
+        .update(account)
+        .set("verified = true")
+        .filter(condition)
+    })
+    .unwrap()
+    .build_with_bindings();
+
+  assert_eq!(
+    "UPDATE Account SET verified = true WHERE id IN $id_0 ; \
+     UPDATE Account SET verified = true WHERE id IN $id_1 ; \
+     UPDATE Account SET verified = true WHERE id IN $id_2",
+    query
+  );
+  assert_eq!(3, bindings.len());
+
+  let empty: [u32; 0] = [];
+  let (query, bindings) = QueryBuilder::new()
+    .chunked_in("settings.theme", &empty, 2, |query, condition| {
+      query.select("*").from(account).filter(condition)
+    })
+    .unwrap()
+    .build_with_bindings();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE settings.theme IN $settings_theme",
+    query
+  );
+  assert_eq!(1, bindings.len());
+}
+
+#[test]
+fn test_dialect() {
+  #[derive(Serialize, Deserialize)]
+  struct Release {
+    id: Option<String>,
+    name: String,
+  }
+
+  impl SurrealCrud<Release> for release::schema::Release<0> {
+    const DIALECT: Dialect = Dialect::V1;
+  }
+
+  let release = Release {
+    id: Some("Release:one".to_owned()),
+    name: "one".to_owned(),
+  };
+
+  let saved = release::schema::model.save(&release).unwrap().build();
+  assert_eq!("UPDATE Release:one CONTENT $content RETURN AFTER", saved);
+
+  let saved = release::schema::model
+    .with_deleted()
+    .save(&release)
+    .unwrap()
+    .build();
+  assert_eq!("UPDATE Release:one CONTENT $content RETURN AFTER", saved);
+
+  let v1 = BuildOptions {
+    dialect: Dialect::V1,
+    ..Default::default()
+  };
+
+  let upsert = QueryBuilder::new()
+    .raw("UPSERT Release:one CONTENT $content")
+    .try_build_with(&v1)
+    .unwrap_err();
+  assert_eq!(
+    "the UPSERT syntax is not supported by SurrealDB 1.x",
+    upsert.to_string()
+  );
+
+  let literal = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("handle = '.{'")
+    .try_build_with(&v1);
+  assert!(literal.is_ok());
+
+  let recursive = QueryBuilder::new()
+    .select(account.ancestors_recursive("knows", 3))
+    .from(account)
+    .try_build_with(&v1);
+  assert_eq!(
+    Err(QueryBuilderError::UnsupportedSyntax {
+      syntax: "recursive path",
+      dialect: Dialect::V1,
+    }),
+    recursive.map(|_| ())
+  );
+}
+
+#[test]
+fn test_define_namespace_and_database() {
+  let bootstrap = QueryBuilder::new()
+    .define_namespace("acme")
+    .comment("it's the Acme tenant")
+    .raw(";")
+    .use_database("acme", "app")
+    .raw(";")
+    .define_database("app")
+    .comment("production")
+    .try_build()
+    .unwrap();
+
+  assert_eq!(
+    r"DEFINE NAMESPACE acme COMMENT 'it\'s the Acme tenant' ; USE NS acme DB app ; DEFINE DATABASE app COMMENT 'production'",
+    bootstrap
+  );
+
+  let teardown = QueryBuilder::new()
+    .remove_database("app")
+    .raw(";")
+    .remove_namespace("acme")
+    .build();
+
+  assert_eq!("REMOVE DATABASE app ; REMOVE NAMESPACE acme", teardown);
+}
+
+#[test]
+fn test_transform_segments() {
+  struct TenantScope;
+
+  impl<'a> QueryFragment<'a> for TenantScope {
+    fn inject(self, query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+      query.transform_segments(|segments| {
+        let is_account = segments
+          .windows(2)
+          .any(|pair| pair[0] == "FROM" && pair[1] == "Account");
+
+        if !is_account {
+          return;
+        }
+
+        let has_where = segments.iter().any(|segment| segment == "WHERE");
+        let keyword = if has_where { "AND" } else { "WHERE" };
+
+        let end = segments
+          .iter()
+          .position(|segment| segment == "LIMIT")
+          .unwrap_or(segments.len());
+
+        segments.splice(end..end, [keyword.into(), "tenant = $tenant".into()]);
+      })
+    }
+  }
+
+  let options = BuildOptions {
+    deny_unbound_parameters: true,
+    ..Default::default()
+  };
+
+  let (query, bindings) = QueryBuilder::new()
+    .apply(TenantScope)
+    .select("*")
+    .from(account)
+    .filter(account.handle.equals_parameterized())
+    .limit(1)
+    .bind("handle", "John")
+    .unwrap()
+    .bind("tenant", "acme")
+    .unwrap()
+    .try_build_with(&options)
+    .unwrap()
+    .into_parts();
+
+  assert_eq!(
+    "SELECT * FROM Account WHERE handle = $handle AND tenant = $tenant LIMIT 1",
+    query
+  );
+  assert_eq!(2, bindings.len());
+
+  let query = QueryBuilder::new()
+    .apply(TenantScope)
+    .select("*")
+    .from(account)
+    .limit(1)
+    .build();
+  assert_eq!(
+    "SELECT * FROM Account WHERE tenant = $tenant LIMIT 1",
+    query
+  );
+
+  let untouched = QueryBuilder::new()
+    .apply(TenantScope)
+    .select("*")
+    .from(project)
+    .build();
+  assert_eq!("SELECT * FROM Project", untouched);
+}
+
+#[test]
+fn test_not_compound_operands() {
+  assert_eq!("!archived", not("archived"));
+  assert_eq!("!$is_admin", not(" $is_admin "));
+  assert_eq!("!(x-1)", not("x-1"));
+  assert_eq!("!(a.b*2)", not("a.b*2"));
+  assert_eq!("!(count(tags))", not("count(tags)"));
+  assert_eq!("!((a) AND (b))", not("(a) AND (b)"));
+}
+
+#[test]
+fn test_transform_segments_raw_text() {
+  fn staging<'a>(query: QueryBuilder<'a>) -> QueryBuilder<'a> {
+    query.transform_segments(|segments| {
+      for segment in segments.iter_mut() {
+        if segment == "Account" {
+          *segment = "staging_Account".into();
+        }
+      }
+    })
+  }
+
+  let query = staging(query!("SELECT * FROM Account WHERE name = 'Account  Doe'")).build();
+  assert_eq!(
+    "SELECT * FROM staging_Account WHERE name = 'Account  Doe'",
+    query
+  );
+
+  let saved = staging(account.save(&Account::default()).unwrap().into_inner()).build();
+  assert_eq!(
+    "CREATE staging_Account CONTENT $content RETURN AFTER",
+    saved
+  );
+
+  let mut query = QueryBuilder::new();
+  query.add_segment("UPDATE Account SET `Account name` = 'Account'");
+  assert_eq!(
+    "UPDATE staging_Account SET `Account name` = 'Account'",
+    staging(query).build()
+  );
+}
+
+#[test]
+fn test_models_from_surql_names_and_types() {
+  use surreal_simple_querybuilder::codegen::models_from_surql;
+
+  let schema = "
+    -- the logs; of the requests
+    DEFINE TABLE HTTPLog SCHEMAFULL;
+    DEFINE FIELD path ON HTTPLog TYPE string ASSERT $value != 'it\\'s; x';
+    # a table named after a keyword
+    DEFINE TABLE type SCHEMAFULL;
+    DEFINE FIELD owner ON type TYPE record<HTTPLog | type>;
+    DEFINE FIELD log ON type TYPE option<record<HTTPLog>>; // the single table
+  ";
+
+  let models = models_from_surql(schema);
+
+  assert!(models.contains("pub mod http_log {"));
+  assert!(models.contains("model!(HTTPLog {\n    id,\n    pub path,\n  });"));
+  assert!(models.contains("pub mod r#type {"));
+  assert!(models.contains("pub owner,"));
+  assert!(models.contains("pub log<HTTPLog>,"));
+  assert!(!models.contains("x'"));
+  assert!(!models.contains("logs"));
+}
+
+#[cfg(feature = "client")]
+#[test]
+fn test_thing_interop() {
+  use surrealdb::sql::Id;
+  use surrealdb::sql::Thing;
+
+  let thing = Thing::from(("Account".to_owned(), Id::String("John Doe".to_owned())));
+  let record = RecordId::from(thing.clone());
+  assert_eq!(RecordId::new("Account", "John Doe"), record);
+  assert_eq!(thing, Thing::from(record));
+
+  let key: TypedKey<Account> = thing.clone().try_into().unwrap();
+  assert_eq!("Account:⟨John Doe⟩", key.as_str());
+  assert_eq!(thing, Thing::from(key));
+
+  let other = Thing::from(("Project".to_owned(), Id::String("one".to_owned())));
+  assert!(TypedKey::<Account>::try_from(other).is_err());
+
+  let number = Thing::from(("Account".to_owned(), Id::Number(42)));
+  assert_eq!(number, Thing::from(RecordId::from(number.clone())));
+  assert_eq!("Account:42", number.to_sql_literal());
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn test_validate_syntax() {
+  let query = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter(account.handle.equals_parameterized())
+    .bind("handle", "John")
+    .unwrap()
+    .validate()
+    .unwrap();
+
+  assert_eq!("SELECT * FROM Account WHERE handle = $handle", query.text());
+  assert_eq!(Ok(()), query.validate());
+
+  let error = QueryBuilder::new()
+    .select("*")
+    .from(account)
+    .filter("handle = !!")
+    .validate()
+    .unwrap_err();
+
+  assert!(matches!(
+    error,
+    QueryBuilderError::InvalidSyntax {
+      position: Some((1, _)),
+      ..
+    }
+  ));
+  assert!(error
+    .to_string()
+    .starts_with("the query is not valid SurrealQL: "));
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn test_foreign_json_schema() {
+  use schemars::gen::SchemaGenerator;
+  use schemars::schema::Schema;
+
+  let mut gen = SchemaGenerator::default();
+  let schema = gen.subschema_for::<Foreign<Vec<u32>, RecordId>>();
+
+  let Schema::Object(object) = schema else {
+    panic!("the schema of a Foreign is not an object");
+  };
+  let any_of = object.subschemas.unwrap().any_of.unwrap();
+
+  assert_eq!(
+    vec![
+      gen.subschema_for::<Option<String>>(),
+      gen.subschema_for::<Vec<u32>>()
+    ],
+    any_of
+  );
+  assert!(gen.definitions().is_empty());
+
+  let links = gen.subschema_for::<ForeignVec<Vec<u32>, RecordId>>();
+  assert_eq!(
+    gen.subschema_for::<Vec<Foreign<Vec<u32>, RecordId>>>(),
+    links
+  );
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn test_openapi_components() {
+  use schemars::gen::SchemaGenerator;
+  use schemars::schema::InstanceType;
+  use schemars::schema::Schema;
+  use schemars::schema::SchemaObject;
+  use schemars::JsonSchema;
+
+  struct Author;
+
+  impl JsonSchema for Author {
+    fn schema_name() -> String {
+      "Author".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+      Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+      })
+    }
+  }
+
+  let components = OpenApiComponents::new()
+    .register::<Foreign<Author>>()
+    .register::<ForeignVec<Author>>()
+    .register::<String>();
+
+  assert_eq!(
+    vec!["Author"],
+    components.schemas().keys().collect::<Vec<_>>()
+  );
+
+  let mut json = serde_json::Map::new();
+  json.insert(
+    "schemas".to_owned(),
+    serde_json::to_value(components.schemas()).unwrap(),
+  );
+
+  assert_eq!(
+    serde_json::Value::Object(json),
+    serde_json::to_value(&components).unwrap()
+  );
+}
+
+#[cfg(feature = "test-harness")]
+mod note {
+  use surreal_simple_querybuilder::prelude::*;
+
+  model!(Note {
+    id,
+    pub title: string,
+    pub stars: int,
+
+    @index note_title(title),
+  });
+}
+
+#[cfg(feature = "test-harness")]
+#[tokio::test]
+async fn test_harness_queries() {
+  use note::schema::model as note;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct NoteRow {
+    title: String,
+    stars: i64,
+  }
+
+  let db = TestDatabase::new().await.unwrap();
+  let registry = SchemaRegistry::new().register(&note);
+  db.define(&registry).await.unwrap();
+
+  QueryBuilder::new()
+    .create("Note:first")
+    .set_model(&note)
+    .unwrap()
+    .bind("title", "the first note")
+    .unwrap()
+    .bind("stars", 3)
+    .unwrap()
+    .fetch_all::<NoteRow, _>(&db)
+    .await
+    .unwrap();
+
+  let rows: Vec<NoteRow> = QueryBuilder::new()
+    .select("title, stars")
+    .from(note)
+    .filter(note.stars.greater_than("$stars"))
+    .bind("stars", 2)
+    .unwrap()
+    .paginate(1, 10)
+    .unwrap()
+    .fetch_all(&db)
+    .await
+    .unwrap();
+
+  assert_eq!(
+    vec![NoteRow {
+      title: "the first note".to_owned(),
+      stars: 3
+    }],
+    rows
+  );
+
+  // the field type is enforced by the database
+  let wrong_type = QueryBuilder::new()
+    .create("Note:second")
+    .set("title = 1, stars = 1");
+  assert!(db.run(wrong_type).await.is_err());
+
+  let drifts = detect_registry_drift(&db, &registry).await.unwrap();
+  assert!(drifts.is_empty(), "{drifts:?}");
+}